@@ -0,0 +1,391 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Seven segments labelled a-g, and seven wires with the same letters.
+/// Patterns are bitmasks with bit 0 for a through bit 6 for g.
+pub const SEGMENTS: usize = 7;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    BadLine(usize, EntryError),
+    BadAlphabet(String),
+    // the explanation of why no wiring works
+    Unsolvable(String),
+    Ambiguous { wirings: usize, undetermined: Vec<char>, readings: Vec<String> }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::BadLine(n, e) => write!(f, "line {}: {}", n, e),
+            DecodeError::BadAlphabet(why) => write!(f, "bad alphabet: {}", why),
+            DecodeError::Unsolvable(why) => write!(f, "no wiring fits: {}", why),
+            DecodeError::Ambiguous { wirings, undetermined, readings } => {
+                let wires:String = undetermined.iter().collect();
+                write!(f, "{} wirings fit, wires '{}' could go to more than one segment", wirings, wires)?;
+                if readings.len() > 1 {
+                    write!(f, " and the output could read {}", readings.join(" or "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EntryError {
+    // no ` | ` between the patterns and the output
+    NoSeparator(String),
+    // a pattern with something other than the letters a to g
+    BadPattern(String)
+}
+
+impl fmt::Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntryError::NoSeparator(s) => write!(f, "'{}' has no ' | ' before the output", s),
+            EntryError::BadPattern(p) => write!(f, "'{}' isn't a pattern of segments a to g", p)
+        }
+    }
+}
+
+fn to_mask(word: &str) -> Option<u8> {
+    word.bytes().try_fold(0, |mask, b| match b {
+        b'a'..=b'g' => Some(mask | 1 << (b - b'a')),
+        _ => None
+    })
+}
+
+fn to_letters(mask: u8) -> String {
+    (0..SEGMENTS as u8).filter(|i| mask & 1 << i != 0).map(|i| (b'a' + i) as char).collect()
+}
+
+const DIGITS: [(char, &str); 10] = [
+    ('0', "abcefg"), ('1', "cf"), ('2', "acdeg"), ('3', "acdfg"), ('4', "bcdf"),
+    ('5', "abdfg"), ('6', "abdefg"), ('7', "acf"), ('8', "abcdefg"), ('9', "abcdfg")
+];
+
+///
+/// The symbols a display can show and which segments light up for each.
+///
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    symbols: Vec<(char, u8)>
+}
+
+impl Alphabet {
+    /// Segments are given as letters, a is the top, b and c the upper left and right,
+    /// d the middle, e and f the lower left and right and g the bottom.
+    pub fn new(symbols: &[(char, &str)]) -> Result<Self, DecodeError> {
+        let mut seen = BTreeSet::new();
+        let mut masks = vec![];
+        for &(c, segments) in symbols {
+            let mask = to_mask(segments)
+                .ok_or_else(|| DecodeError::BadAlphabet(format!("'{}' isn't a set of segments", segments)))?;
+            if !seen.insert(mask) {
+                return Err(DecodeError::BadAlphabet(format!("'{}' looks the same as another symbol", c)))
+            }
+            masks.push((c, mask));
+        }
+        Ok(Alphabet { symbols: masks })
+    }
+
+    pub fn decimal() -> Self {
+        Alphabet::new(&DIGITS).unwrap()
+    }
+
+    /// Decimal digits plus A b C d E F.
+    pub fn hex() -> Self {
+        let letters = [('A', "abcdef"), ('b', "bdefg"), ('C', "abeg"), ('d', "cdefg"), ('E', "abdeg"), ('F', "abde")];
+        Alphabet::new(&[&DIGITS[..], &letters].concat()).unwrap()
+    }
+
+    pub fn symbol(&self, mask: u8) -> Option<char> {
+        self.symbols.iter().find(|(_, m)| *m == mask).map(|(c, _)| *c)
+    }
+
+    /// Could `pattern` be a symbol, given what we know about some of its wires?
+    /// `lit` are segments we know are on, `dark` ones we know are off.
+    fn fits(&self, count: u32, lit: u8, dark: u8) -> bool {
+        self.symbols.iter().any(|&(_, m)| m.count_ones() == count && m & lit == lit && m & dark == 0)
+    }
+
+    /// True when only one symbol uses this many segments, so it's known without any wiring.
+    pub fn unique_length(&self, count: u32) -> bool {
+        self.symbols.iter().filter(|(_, m)| m.count_ones() == count).count() == 1
+    }
+}
+
+///
+/// Which segment each wire is connected to.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wiring([u8; SEGMENTS]);
+
+impl Wiring {
+    /// The segments lit when the wires in `pattern` are on.
+    pub fn segments(&self, pattern: u8) -> u8 {
+        (0..SEGMENTS).filter(|w| pattern & 1 << w != 0).fold(0, |m, w| m | 1 << self.0[w])
+    }
+}
+
+impl fmt::Display for Wiring {
+    /// The segment for wire a, then b, and so on.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s:String = self.0.iter().map(|&s| (b'a' + s) as char).collect();
+        write!(f, "{}", s)
+    }
+}
+
+///
+/// One line of notes: the ten scrambled patterns and the four output patterns.
+///
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub patterns: Vec<u8>,
+    pub output: Vec<u8>
+}
+
+impl FromStr for Entry {
+    type Err = EntryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (patterns, output) = s.trim().split_once(" | ").ok_or_else(|| EntryError::NoSeparator(s.to_string()))?;
+        let masks = |words: &str| {
+            words.split_whitespace()
+                .map(|w| to_mask(w).ok_or_else(|| EntryError::BadPattern(w.to_string())))
+                .collect::<Result<Vec<u8>, _>>()
+        };
+        Ok(Entry { patterns: masks(patterns)?, output: masks(output)? })
+    }
+}
+
+impl Entry {
+    fn observed(&self) -> Vec<u8> {
+        let mut all:Vec<u8> = self.patterns.iter().chain(&self.output).copied().collect();
+        all.sort();
+        all.dedup();
+        all
+    }
+
+    /// Every wiring that makes each pattern, and the output, a symbol in the alphabet.
+    pub fn wirings(&self, alphabet: &Alphabet) -> Vec<Wiring> {
+        let mut found = vec![];
+        search(alphabet, &self.observed(), &mut [0; SEGMENTS], 0, 0, &mut found);
+        found
+    }
+
+    /// The one wiring that fits, or an explanation of why there isn't exactly one.
+    pub fn solve(&self, alphabet: &Alphabet) -> Result<Wiring, DecodeError> {
+        let wirings = self.wirings(alphabet);
+        match wirings.len() {
+            0 => Err(self.unsolvable(alphabet)),
+            1 => Ok(wirings[0]),
+            _ => Err(self.ambiguous(alphabet, &wirings))
+        }
+    }
+
+    /// The output symbols. Several wirings are fine as long as they all read the same.
+    pub fn read(&self, alphabet: &Alphabet) -> Result<String, DecodeError> {
+        let wirings = self.wirings(alphabet);
+        let readings = self.readings(alphabet, &wirings);
+        match readings.len() {
+            0 => Err(self.unsolvable(alphabet)),
+            1 => Ok(readings.into_iter().next().unwrap()),
+            _ => Err(self.ambiguous(alphabet, &wirings))
+        }
+    }
+
+    fn readings(&self, alphabet: &Alphabet, wirings: &[Wiring]) -> Vec<String> {
+        let readings:BTreeSet<String> = wirings.iter()
+            .map(|w| self.output.iter().filter_map(|&p| alphabet.symbol(w.segments(p))).collect())
+            .collect();
+        readings.into_iter().collect()
+    }
+
+    fn ambiguous(&self, alphabet: &Alphabet, wirings: &[Wiring]) -> DecodeError {
+        let undetermined = (0..SEGMENTS)
+            .filter(|&w| wirings.iter().any(|wiring| wiring.0[w] != wirings[0].0[w]))
+            .map(|w| (b'a' + w as u8) as char)
+            .collect();
+        DecodeError::Ambiguous { wirings: wirings.len(), undetermined, readings: self.readings(alphabet, wirings) }
+    }
+
+    // Adds patterns one at a time to find the first one that can't fit
+    fn unsolvable(&self, alphabet: &Alphabet) -> DecodeError {
+        let observed = self.observed();
+        for (k, &pattern) in observed.iter().enumerate() {
+            let count = pattern.count_ones();
+            if !alphabet.fits(count, 0, 0) {
+                return DecodeError::Unsolvable(format!("'{}' has {} segments and no symbol does", to_letters(pattern), count))
+            }
+            let mut found = vec![];
+            search(alphabet, &observed[..=k], &mut [0; SEGMENTS], 0, 0, &mut found);
+            if found.is_empty() {
+                let before:Vec<String> = observed[..k].iter().map(|&p| to_letters(p)).collect();
+                return DecodeError::Unsolvable(format!("'{}' can't be a symbol alongside {}", to_letters(pattern), before.join(" ")))
+            }
+        }
+        DecodeError::Unsolvable("no patterns".to_string())
+    }
+}
+
+// Tries every segment for each wire in turn, dropping a branch as soon as
+// some pattern can't be any symbol with the wires assigned so far.
+fn search(alphabet: &Alphabet, patterns: &[u8], wiring: &mut [u8; SEGMENTS], wire: usize, used: u8, found: &mut Vec<Wiring>) {
+    let consistent = patterns.iter().all(|&p| {
+        let (lit, dark) = (0..wire).fold((0, 0), |(lit, dark), w| {
+            if p & 1 << w != 0 { (lit | 1 << wiring[w], dark) } else { (lit, dark | 1 << wiring[w]) }
+        });
+        alphabet.fits(p.count_ones(), lit, dark)
+    });
+    if !consistent {
+        return
+    }
+    if wire == SEGMENTS {
+        found.push(Wiring(*wiring));
+        return
+    }
+    for segment in 0..SEGMENTS as u8 {
+        if used & 1 << segment == 0 {
+            wiring[wire] = segment;
+            search(alphabet, patterns, wiring, wire + 1, used | 1 << segment, found);
+        }
+    }
+}
+
+pub fn parse_data(data: &str) -> Result<Vec<Entry>, DecodeError> {
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| line.parse().map_err(|e| DecodeError::BadLine(i + 1, e)))
+        .collect()
+}
+
+/// How many output patterns are symbols we can tell apart by length alone.
+pub fn count_unique(entries: &[Entry], alphabet: &Alphabet) -> usize {
+    entries.iter()
+        .flat_map(|e| &e.output)
+        .filter(|p| alphabet.unique_length(p.count_ones()))
+        .count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static LINE: &str = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+    static EXAMPLE: &str = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
+
+    #[test]
+    fn test_parse_line(){
+        let entry:Entry = LINE.parse().unwrap();
+        assert_eq!(entry.patterns.len(), 10);
+        assert_eq!(entry.output, vec![to_mask("bcdef").unwrap(), to_mask("abcdf").unwrap(), to_mask("bcdef").unwrap(), to_mask("abcdf").unwrap()]);
+        assert_eq!(parse_data("ab | ab\nab | zz").unwrap_err(), DecodeError::BadLine(2, EntryError::BadPattern("zz".to_string())));
+    }
+    #[test]
+    fn test_wires(){
+        let entry:Entry = LINE.parse().unwrap();
+        let alphabet = Alphabet::decimal();
+        let wiring = entry.solve(&alphabet).unwrap();
+        assert_eq!(wiring.to_string(), "cfgabde");
+        let digit = |w: &str| alphabet.symbol(wiring.segments(to_mask(w).unwrap())).unwrap();
+        assert_eq!(digit("acedgfb"), '8');
+        assert_eq!(digit("cdfbe"), '5');
+        assert_eq!(digit("gcdfa"), '2');
+        assert_eq!(digit("fbcad"), '3');
+        assert_eq!(digit("dab"), '7');
+        assert_eq!(digit("cefabd"), '9');
+        assert_eq!(digit("cdfgeb"), '6');
+        assert_eq!(digit("eafb"), '4');
+        assert_eq!(digit("cagedb"), '0');
+        assert_eq!(digit("ab"), '1');
+    }
+
+    #[test]
+    fn test_word(){
+        let entry:Entry = LINE.parse().unwrap();
+        assert_eq!(entry.read(&Alphabet::decimal()), Ok("5353".to_string()));
+    }
+
+    #[test]
+    fn test_example(){
+        let alphabet = Alphabet::decimal();
+        let entries = parse_data(EXAMPLE).unwrap();
+        assert_eq!(count_unique(&entries, &alphabet), 26);
+        let total:u64 = entries.iter().map(|e| e.read(&alphabet).unwrap().parse::<u64>().unwrap()).sum();
+        assert_eq!(total, 61229);
+    }
+
+    #[test]
+    fn test_ambiguous(){
+        let alphabet = Alphabet::decimal();
+        let entry:Entry = "ab | ab ba".parse().unwrap();
+        // a and b are c and f in some order, the rest could be anything
+        assert_eq!(entry.wirings(&alphabet).len(), 2 * 120);
+        assert_eq!(entry.read(&alphabet), Ok("11".to_string()));
+
+        let entry:Entry = "ab abc | abc".parse().unwrap();
+        let Err(DecodeError::Ambiguous { wirings, undetermined, readings }) = entry.solve(&alphabet) else {
+            panic!("expected an ambiguous wiring")
+        };
+        assert_eq!(wirings, 2 * 24);
+        assert_eq!(undetermined, vec!['a', 'b', 'd', 'e', 'f', 'g']);
+        assert_eq!(readings, vec!["7".to_string()]);
+    }
+
+    #[test]
+    fn test_unsolvable(){
+        let alphabet = Alphabet::decimal();
+        let entry:Entry = "ab abc abcd | ab".parse().unwrap();
+        assert_eq!(
+            entry.solve(&alphabet),
+            Err(DecodeError::Unsolvable("'abcd' can't be a symbol alongside ab abc".to_string()))
+        );
+        let entry:Entry = "ab | abcdef".parse().unwrap();
+        let small = Alphabet::new(&[('1', "cf"), ('7', "acf")]).unwrap();
+        assert_eq!(
+            entry.read(&small),
+            Err(DecodeError::Unsolvable("'abcdef' has 6 segments and no symbol does".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hex(){
+        let hex = Alphabet::hex();
+        // wire w lights segment (w + 3) % 7
+        let wiring = Wiring([3, 4, 5, 6, 0, 1, 2]);
+        let scramble = |c: char| {
+            let mask = hex.symbols.iter().find(|(s, _)| *s == c).unwrap().1;
+            let wires = (0..SEGMENTS).filter(|&w| mask & 1 << wiring.0[w] != 0).fold(0, |m, w| m | 1 << w);
+            to_letters(wires)
+        };
+        let patterns:Vec<String> = "0123456789AbCdEF".chars().map(scramble).collect();
+        let output:Vec<String> = "bEEF".chars().map(scramble).collect();
+        let entry:Entry = format!("{} | {}", patterns.join(" "), output.join(" ")).parse().unwrap();
+        assert_eq!(entry.solve(&hex), Ok(wiring));
+        assert_eq!(entry.read(&hex), Ok("bEEF".to_string()));
+
+        assert!(Alphabet::new(&[('0', "abc"), ('O', "cba")]).is_err());
+        assert!(Alphabet::new(&[('0', "xyz")]).is_err());
+    }
+
+    #[test]
+    fn test_bad_entry() {
+        assert_eq!("ab cd".parse::<Entry>(), Err(EntryError::NoSeparator("ab cd".to_string())));
+        assert_eq!("ab | cx".parse::<Entry>(), Err(EntryError::BadPattern("cx".to_string())));
+        assert_eq!(parse_data("ab | cd\n\nab cd"), Err(DecodeError::BadLine(3, EntryError::NoSeparator("ab cd".to_string()))));
+    }
+}
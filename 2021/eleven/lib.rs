@@ -1,11 +0,0 @@
-use ndarray::arr2;
-
-struct Grid {
-    w: usize,
-    h: usize,
-    rows: Vec<Vec<Octo>>
-}
-
-impl Grid {
-
-}
\ No newline at end of file
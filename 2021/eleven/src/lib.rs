@@ -0,0 +1,232 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use ndarray::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    Four,
+    Eight
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+    Empty,
+    // line and column of a character that isn't a digit
+    BadEnergy(usize, usize),
+    Ragged(usize)
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseGridError::Empty => write!(f, "no octopuses found"),
+            ParseGridError::BadEnergy(line, col) => write!(f, "line {} column {}: not an energy level", line, col),
+            ParseGridError::Ragged(line) => write!(f, "line {}: rows are different lengths", line)
+        }
+    }
+}
+
+/// What happened when the grid was run.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    // every octopus flashed on this step
+    Synchronized(usize),
+    // the grid came back to the state it was in at `start`
+    // without ever synchronizing
+    Cycle { start: usize, length: usize }
+}
+
+pub fn neighbors(p: (usize, usize), shape: (usize, usize), neighborhood: Neighborhood) -> Vec<(usize, usize)> {
+    let (rows, cols) = shape;
+    let mut points = Vec::with_capacity(8);
+    for row in p.0.saturating_sub(1)..(p.0 + 2).min(rows) {
+        for col in p.1.saturating_sub(1)..(p.1 + 2).min(cols) {
+            let diagonal = row != p.0 && col != p.1;
+            if (row, col) != p && (neighborhood == Neighborhood::Eight || !diagonal) {
+                points.push((row, col))
+            }
+        }
+    }
+    points
+}
+
+///
+/// The octopuses' energy levels. Any octopus whose energy goes above
+/// `threshold` flashes, adding one to its neighbors, and drops to zero.
+///
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub energy: Array2<u32>,
+    pub threshold: u32,
+    pub neighborhood: Neighborhood,
+    pub steps: usize
+}
+
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for (i, line) in s.lines().map(|l| l.trim()).enumerate().filter(|(_, l)| !l.is_empty()) {
+            for (j, c) in line.chars().enumerate() {
+                values.push(c.to_digit(10).ok_or(ParseGridError::BadEnergy(i + 1, j + 1))?);
+            }
+            if *width.get_or_insert(line.len()) != line.len() {
+                return Err(ParseGridError::Ragged(i + 1))
+            }
+            height += 1;
+        }
+        let width = width.ok_or(ParseGridError::Empty)?;
+        let energy = Array2::from_shape_vec((height, width), values).map_err(|_| ParseGridError::Empty)?;
+        Ok(Grid::new(energy, 9, Neighborhood::Eight))
+    }
+}
+
+impl Grid {
+    pub fn new(energy: Array2<u32>, threshold: u32, neighborhood: Neighborhood) -> Grid {
+        Grid { energy, threshold, neighborhood, steps: 0 }
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        self.energy.dim()
+    }
+
+    /// Runs one step and returns the octopuses that flashed, in the order they flashed.
+    pub fn step(&mut self) -> Vec<(usize, usize)> {
+        self.steps += 1;
+        self.energy += 1;
+
+        let threshold = self.threshold;
+        let mut flashed = Array2::from_elem(self.shape(), false);
+        let mut queue:VecDeque<(usize, usize)> = self.energy.indexed_iter()
+            .filter(|(_, &e)| e > threshold)
+            .map(|(p, _)| p)
+            .collect();
+        queue.iter().for_each(|p| flashed[*p] = true);
+
+        let mut flashes = vec![];
+        while let Some(next) = queue.pop_front() {
+            flashes.push(next);
+            for p in neighbors(next, self.shape(), self.neighborhood) {
+                self.energy[p] += 1;
+                if self.energy[p] > threshold && !flashed[p] {
+                    flashed[p] = true;
+                    queue.push_back(p);
+                }
+            }
+        }
+        for p in &flashes {
+            self.energy[*p] = 0;
+        }
+        flashes
+    }
+
+    /// Total flashes over the next `n` steps.
+    pub fn count_flashes(&mut self, n: usize) -> usize {
+        (0..n).map(|_| self.step().len()).sum()
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.energy.iter().all(|e| *e == 0)
+    }
+
+    /// Steps until every octopus flashes at once. Since the grid is
+    /// deterministic and finite it either synchronizes or repeats, so
+    /// seen states are remembered to spot a cycle instead of giving up
+    /// after a fixed number of steps.
+    pub fn run(&mut self) -> Outcome {
+        let mut seen:HashMap<Array2<u32>, usize> = HashMap::new();
+        loop {
+            if let Some(start) = seen.insert(self.energy.clone(), self.steps) {
+                return Outcome::Cycle { start, length: self.steps - start }
+            }
+            let flashes = self.step();
+            if flashes.len() == self.energy.len() {
+                return Outcome::Synchronized(self.steps)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static EXAMPLE: &str = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+
+    #[test]
+    fn test_neighbors() {
+        let ns = neighbors((0, 0), (5, 5), Neighborhood::Eight);
+        assert_eq!(ns, vec![(0,1),(1,0),(1,1)]);
+
+        let ns = neighbors((4, 4), (5, 5), Neighborhood::Eight);
+        assert_eq!(ns, vec![(3,3),(3,4),(4,3)]);
+
+        let ns = neighbors((1, 1), (5, 5), Neighborhood::Eight);
+        assert_eq!(ns, vec![(0,0), (0,1), (0,2), (1,0), (1,2), (2,0), (2,1), (2,2)]);
+
+        // columns are bounded by the width, not the height
+        let ns = neighbors((1, 5), (2, 6), Neighborhood::Eight);
+        assert_eq!(ns, vec![(0,4), (0,5), (1,4)]);
+
+        let ns = neighbors((1, 1), (5, 5), Neighborhood::Four);
+        assert_eq!(ns, vec![(0,1), (1,0), (1,2), (2,1)]);
+    }
+
+    #[test]
+    fn test_example() {
+        let mut grid:Grid = EXAMPLE.parse().unwrap();
+        assert_eq!(grid.count_flashes(10), 204);
+        assert_eq!(grid.count_flashes(90), 1656 - 204);
+
+        let mut grid:Grid = EXAMPLE.parse().unwrap();
+        assert_eq!(grid.run(), Outcome::Synchronized(195));
+    }
+
+    #[test]
+    fn test_flash_list() {
+        let mut grid:Grid = "11111\n19991\n19191\n19991\n11111".parse().unwrap();
+        let flashes = grid.step();
+        assert_eq!(flashes.len(), 9);
+        assert_eq!(&flashes[..3], &[(1, 1), (1, 2), (1, 3)]);
+        assert_eq!(grid.energy.row(0).to_vec(), vec![3, 4, 5, 4, 3]);
+    }
+
+    #[test]
+    fn test_shapes_and_cycles() {
+        let mut grid:Grid = "123\n456".parse().unwrap();
+        assert_eq!(grid.shape(), (2, 3));
+        assert_eq!(grid.run(), Outcome::Synchronized(61));
+
+        // a lone octopus flashes in sync with itself every ten steps
+        let mut grid = Grid::new(array![[1]], 9, Neighborhood::Four);
+        assert_eq!(grid.run(), Outcome::Synchronized(9));
+
+        // these never line up and fall into a loop instead
+        let mut grid:Grid = "002".parse().unwrap();
+        assert_eq!(grid.run(), Outcome::Cycle { start: 0, length: 9 });
+
+        let mut grid:Grid = "00\n12".parse().unwrap();
+        grid.neighborhood = Neighborhood::Four;
+        assert_eq!(grid.run(), Outcome::Cycle { start: 8, length: 9 });
+
+        assert_eq!("12\n3".parse::<Grid>().unwrap_err(), ParseGridError::Ragged(2));
+        assert_eq!("12\n3x".parse::<Grid>().unwrap_err(), ParseGridError::BadEnergy(2, 2));
+        // blank lines still count towards the line number
+        assert_eq!("12\n\n3x".parse::<Grid>().unwrap_err(), ParseGridError::BadEnergy(3, 2));
+        assert_eq!("\n12\n\n3".parse::<Grid>().unwrap_err(), ParseGridError::Ragged(4));
+    }
+}
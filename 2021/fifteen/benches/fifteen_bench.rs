@@ -0,0 +1,47 @@
+//! Benchmarks
+#[macro_use]
+extern crate bencher;
+extern crate fifteen;
+use self::bencher::Bencher;
+
+use fifteen::{Graph, make_large_graph};
+use fifteen::shortest::{astar, dial, dijkstra, Tiled};
+
+// puzzle sized risk levels without needing data.txt, uneven enough
+// that the cheapest route has to wander
+fn make_matrix() -> Vec<Vec<usize>> {
+    (0..100).map(|y| (0..100).map(|x| (x * x + 3 * y + x * y / 7) % 9 + 1).collect()).collect()
+}
+
+fn bench_hashmap_graph(b: &mut Bencher) {
+    let matrix = make_matrix();
+
+    b.iter(|| {
+        let g = Graph::new(&make_large_graph(&matrix, 5));
+        g.dijkstra((0, 0), (499, 499))
+    })
+}
+
+fn bench_dijkstra(b: &mut Bencher) {
+    let matrix = make_matrix();
+    b.iter(|| dijkstra(&Tiled::new(&matrix, 5), (0, 0), (499, 499)))
+}
+
+fn bench_astar(b: &mut Bencher) {
+    let matrix = make_matrix();
+    b.iter(|| astar(&Tiled::new(&matrix, 5), (0, 0), (499, 499)))
+}
+
+fn bench_dial(b: &mut Bencher) {
+    let matrix = make_matrix();
+    b.iter(|| dial(&Tiled::new(&matrix, 5), (0, 0), (499, 499)))
+}
+
+benchmark_group!(
+    benches,
+    bench_hashmap_graph,
+    bench_dijkstra,
+    bench_astar,
+    bench_dial
+);
+benchmark_main!(benches);
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, BinaryHeap};
 use std::cmp::Ordering;
 
+pub mod shortest;
+
 type Matrix = Vec<Vec<usize>>;
 
 pub fn neighbors(p:(usize, usize), max_w:isize, max_h:isize) -> Vec<(usize, usize)>{
@@ -63,7 +65,7 @@ impl Graph {
                 let mut next_node = Node {edges: Vec::new()};
                 for dest in neighbors((x, y), width, height) {
                     let cost = ref_matrix[dest.1][dest.0];
-                    next_node.edges.push(Edge {cost: cost as usize, dest});
+                    next_node.edges.push(Edge {cost, dest});
                 }
                 nodes.insert((x, y), next_node);
             }
@@ -82,7 +84,7 @@ impl Graph {
             if current_node.node == target {
                 return Some(current_node.cost)
             }
-            if current_node.cost > *distances.entry(current_node.node.clone()).or_insert(usize::MAX) {
+            if current_node.cost > *distances.entry(current_node.node).or_insert(usize::MAX) {
                 // we've already found a cheaper way to get there
                 continue
             }
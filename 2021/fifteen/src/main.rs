@@ -1,7 +1,7 @@
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
-use fifteen::*;
+use fifteen::shortest::{astar, CostMap, Grid, Tiled};
 
 const PATH: &str = "./data.txt";
 
@@ -18,16 +18,16 @@ fn main() {
     let path = Path::new(PATH);
     let file = File::open(path).expect("Your file got lost in the cave");
     let matrix = parse_input(file);
-    let g = Graph::new(&matrix);
-    let w = matrix[0].len();
-    let h = matrix.len();
+    let grid = Grid::new(&matrix);
+    let end = grid.corner().expect("There's no cave in the file");
 
-    println!("solution 1: {:?}", g.dijkstra((0, 0), (w-1, h-1)));
+    let route = astar(&grid, (0, 0), end);
+    println!("solution 1: {:?}", route.map(|r| r.cost));
 
-    let biggy = make_large_graph(&matrix, 5);
-    let g2 = Graph::new(&biggy);
-    let w = biggy[0].len();
-    let h = biggy.len();
-    println!("solution 2: {:?}", g2.dijkstra((0, 0), (w-1, h-1)));
+    // the big map is only ever looked at through the tiled view
+    let tiled = Tiled::new(&matrix, 5);
+    let end = tiled.corner().expect("There's no cave in the file");
+    let route = astar(&tiled, (0, 0), end);
+    println!("solution 2: {:?}", route.map(|r| r.cost));
 
 }
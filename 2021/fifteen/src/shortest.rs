@@ -0,0 +1,379 @@
+use std::collections::BinaryHeap;
+use crate::CostNode;
+
+pub type Point = (usize, usize);
+type Matrix = Vec<Vec<usize>>;
+
+/// A view of a grid that can say what it costs to enter each point.
+/// Points are (x, y). Nothing is materialised, so views can describe
+/// maps much bigger than the data behind them.
+pub trait CostMap {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+
+    /// The cost of stepping onto `p`, or None if it can't be entered.
+    fn cost(&self, p: Point) -> Option<usize>;
+
+    /// The cheapest possible step, used to keep the A* heuristic admissible.
+    fn min_cost(&self) -> usize {
+        0
+    }
+
+    /// The most expensive step, which sizes dial's bucket queue.
+    /// Every map has to say, there's no safe default.
+    fn max_cost(&self) -> usize;
+
+    /// The bottom right point, or None if there's nothing to the map.
+    fn corner(&self) -> Option<Point> {
+        Some((self.width().checked_sub(1)?, self.height().checked_sub(1)?))
+    }
+
+    fn neighbors(&self, p: Point) -> [Option<Point>; 4] {
+        let (w, h) = (self.width(), self.height());
+        [
+            (p.0 + 1 < w).then(|| (p.0 + 1, p.1)),
+            p.0.checked_sub(1).map(|x| (x, p.1)),
+            (p.1 + 1 < h).then(|| (p.0, p.1 + 1)),
+            p.1.checked_sub(1).map(|y| (p.0, y))
+        ]
+    }
+
+    /// A lower bound on the cost from one point to another.
+    fn heuristic(&self, from: Point, to: Point) -> usize {
+        (from.0.abs_diff(to.0) + from.1.abs_diff(to.1)) * self.min_cost()
+    }
+}
+
+fn matrix_costs(matrix: &Matrix) -> (usize, usize) {
+    let all = matrix.iter().flatten();
+    (all.clone().copied().min().unwrap_or(0), all.copied().max().unwrap_or(0))
+}
+
+/// The matrix as it is.
+pub struct Grid<'a> {
+    matrix: &'a Matrix,
+    min: usize,
+    max: usize
+}
+
+impl<'a> Grid<'a> {
+    pub fn new(matrix: &'a Matrix) -> Grid<'a> {
+        let (min, max) = matrix_costs(matrix);
+        Grid { matrix, min, max }
+    }
+}
+
+impl CostMap for Grid<'_> {
+    fn width(&self) -> usize { self.matrix.first().map_or(0, |r| r.len()) }
+    fn height(&self) -> usize { self.matrix.len() }
+    fn min_cost(&self) -> usize { self.min }
+    fn max_cost(&self) -> usize { self.max }
+
+    fn cost(&self, p: Point) -> Option<usize> {
+        self.matrix.get(p.1)?.get(p.0).copied()
+    }
+}
+
+/// The matrix repeated `factor` times in each direction, with every tile
+/// to the right or down adding one to the risk and wrapping 9 back to 1.
+/// This is the same map `make_large_graph` builds without the copying.
+/// A 0 counts up like any other risk, but is only 0 in the first tile.
+pub struct Tiled<'a> {
+    grid: Grid<'a>,
+    factor: usize
+}
+
+impl<'a> Tiled<'a> {
+    pub fn new(matrix: &'a Matrix, factor: usize) -> Tiled<'a> {
+        Tiled { grid: Grid::new(matrix), factor }
+    }
+}
+
+impl CostMap for Tiled<'_> {
+    fn width(&self) -> usize { self.grid.width() * self.factor }
+    fn height(&self) -> usize { self.grid.height() * self.factor }
+    fn min_cost(&self) -> usize { self.grid.min.min(1) }
+    fn max_cost(&self) -> usize { 9 }
+
+    fn cost(&self, p: Point) -> Option<usize> {
+        if p.0 >= self.width() || p.1 >= self.height() {
+            return None
+        }
+        let (w, h) = (self.grid.width(), self.grid.height());
+        let n = self.grid.cost((p.0 % w, p.1 % h))? + p.0 / w + p.1 / h;
+        Some(if n == 0 { 0 } else { (n - 1) % 9 + 1 })
+    }
+}
+
+/// Any map, with the edges joined so walking off one side
+/// comes back on the other.
+pub struct Wrapped<M: CostMap>(pub M);
+
+impl<M: CostMap> CostMap for Wrapped<M> {
+    fn width(&self) -> usize { self.0.width() }
+    fn height(&self) -> usize { self.0.height() }
+    fn min_cost(&self) -> usize { self.0.min_cost() }
+    fn max_cost(&self) -> usize { self.0.max_cost() }
+
+    fn cost(&self, p: Point) -> Option<usize> {
+        self.0.cost(p)
+    }
+
+    fn neighbors(&self, p: Point) -> [Option<Point>; 4] {
+        let (w, h) = (self.width(), self.height());
+        if w == 0 || h == 0 {
+            return [None; 4]
+        }
+        [
+            Some(((p.0 + 1) % w, p.1)),
+            Some(((p.0 + w - 1) % w, p.1)),
+            Some((p.0, (p.1 + 1) % h)),
+            Some((p.0, (p.1 + h - 1) % h))
+        ]
+    }
+
+    fn heuristic(&self, from: Point, to: Point) -> usize {
+        let dx = from.0.abs_diff(to.0);
+        let dy = from.1.abs_diff(to.1);
+        (dx.min(self.width() - dx) + dy.min(self.height() - dy)) * self.min_cost()
+    }
+}
+
+/// A map described entirely by a function.
+pub struct CostFn<F: Fn(Point) -> Option<usize>> {
+    pub width: usize,
+    pub height: usize,
+    pub max: usize,
+    pub f: F
+}
+
+impl<F: Fn(Point) -> Option<usize>> CostMap for CostFn<F> {
+    fn width(&self) -> usize { self.width }
+    fn height(&self) -> usize { self.height }
+    fn max_cost(&self) -> usize { self.max }
+
+    fn cost(&self, p: Point) -> Option<usize> {
+        (self.f)(p)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Route {
+    pub cost: usize,
+    pub path: Vec<Point>
+}
+
+// Distances and the point we came from, indexed by y * width + x
+struct Search {
+    width: usize,
+    distances: Vec<usize>,
+    previous: Vec<Option<Point>>
+}
+
+impl Search {
+    fn new<M: CostMap>(map: &M, source: Point) -> Search {
+        let mut s = Search {
+            width: map.width(),
+            distances: vec![usize::MAX; map.width() * map.height()],
+            previous: vec![None; map.width() * map.height()]
+        };
+        let i = s.index(source);
+        s.distances[i] = 0;
+        s
+    }
+
+    fn index(&self, p: Point) -> usize {
+        p.1 * self.width + p.0
+    }
+
+    fn distance(&self, p: Point) -> usize {
+        self.distances[self.index(p)]
+    }
+
+    // Records a step if it's cheaper than what we had
+    fn relax(&mut self, from: Point, to: Point, cost: usize) -> bool {
+        let i = self.index(to);
+        if cost < self.distances[i] {
+            self.distances[i] = cost;
+            self.previous[i] = Some(from);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn route(&self, target: Point) -> Route {
+        let mut path = vec![target];
+        while let Some(p) = self.previous[self.index(*path.last().unwrap())] {
+            path.push(p);
+        }
+        path.reverse();
+        Route { cost: self.distance(target), path }
+    }
+}
+
+fn in_bounds<M: CostMap>(map: &M, p: Point) -> bool {
+    p.0 < map.width() && p.1 < map.height()
+}
+
+fn best_first<M: CostMap>(map: &M, source: Point, target: Point, estimate: impl Fn(Point) -> usize) -> Option<Route> {
+    if !in_bounds(map, source) || !in_bounds(map, target) {
+        return None
+    }
+    let mut search = Search::new(map, source);
+    let mut heap = BinaryHeap::new();
+    // The heap is ordered by cost so far plus the estimate to go
+    heap.push(CostNode { node: source, cost: estimate(source) });
+
+    while let Some(CostNode { node, cost }) = heap.pop() {
+        if node == target {
+            return Some(search.route(target))
+        }
+        let so_far = search.distance(node);
+        if cost > so_far + estimate(node) {
+            // we've already found a cheaper way to get there
+            continue
+        }
+        for next in map.neighbors(node).into_iter().flatten() {
+            let Some(step) = map.cost(next) else { continue };
+            if search.relax(node, next, so_far + step) {
+                heap.push(CostNode { node: next, cost: so_far + step + estimate(next) });
+            }
+        }
+    }
+    None
+}
+
+/// Plain Dijkstra with a binary heap.
+pub fn dijkstra<M: CostMap>(map: &M, source: Point, target: Point) -> Option<Route> {
+    best_first(map, source, target, |_| 0)
+}
+
+/// A* guided by the map's heuristic, Manhattan distance unless the map says otherwise.
+pub fn astar<M: CostMap>(map: &M, source: Point, target: Point) -> Option<Route> {
+    best_first(map, source, target, |p| map.heuristic(p, target))
+}
+
+/// Dijkstra with a bucket queue (dial's algorithm). Costs are small integers,
+/// so a ring of `max_cost + 1` buckets replaces the heap.
+///
+/// Panics if the map has a step dearer than its `max_cost`, which would
+/// otherwise land in the wrong bucket and quietly give the wrong route.
+pub fn dial<M: CostMap>(map: &M, source: Point, target: Point) -> Option<Route> {
+    if !in_bounds(map, source) || !in_bounds(map, target) {
+        return None
+    }
+    let ring = map.max_cost() + 1;
+    let mut buckets:Vec<Vec<Point>> = vec![vec![]; ring];
+    let mut search = Search::new(map, source);
+    let mut queued = 1;
+    let mut current = 0;
+    buckets[0].push(source);
+
+    while queued > 0 {
+        let Some(node) = buckets[current % ring].pop() else {
+            current += 1;
+            continue
+        };
+        queued -= 1;
+        if search.distance(node) != current {
+            // stale entry, a cheaper one was already handled
+            continue
+        }
+        if node == target {
+            return Some(search.route(target))
+        }
+        for next in map.neighbors(node).into_iter().flatten() {
+            let Some(step) = map.cost(next) else { continue };
+            assert!(step < ring, "a step costing {} is more than the map's max_cost of {}", step, ring - 1);
+            if search.relax(node, next, current + step) {
+                buckets[(current + step) % ring].push(next);
+                queued += 1;
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::make_large_graph;
+
+    static EXAMPLE: &str = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+    fn matrix() -> Matrix {
+        EXAMPLE.lines().map(|l| l.chars().map(|c| c.to_digit(10).unwrap() as usize).collect()).collect()
+    }
+
+    #[test]
+    fn test_small() {
+        let m = matrix();
+        let grid = Grid::new(&m);
+        let d = dijkstra(&grid, (0, 0), (9, 9)).unwrap();
+        assert_eq!(d.cost, 40);
+        assert_eq!(d.path.first(), Some(&(0, 0)));
+        assert_eq!(d.path.last(), Some(&(9, 9)));
+        assert_eq!(d.path.iter().skip(1).map(|p| grid.cost(*p).unwrap()).sum::<usize>(), 40);
+        assert_eq!(astar(&grid, (0, 0), (9, 9)).unwrap().cost, 40);
+        assert_eq!(dial(&grid, (0, 0), (9, 9)).unwrap().cost, 40);
+    }
+
+    #[test]
+    fn test_tiled() {
+        let m = matrix();
+        let tiled = Tiled::new(&m, 5);
+        let big = make_large_graph(&m, 5);
+        for (y, row) in big.iter().enumerate() {
+            for (x, n) in row.iter().enumerate() {
+                assert_eq!(tiled.cost((x, y)), Some(*n));
+            }
+        }
+        assert_eq!(dijkstra(&tiled, (0, 0), (49, 49)).unwrap().cost, 315);
+        assert_eq!(astar(&tiled, (0, 0), (49, 49)).unwrap().cost, 315);
+        assert_eq!(dial(&tiled, (0, 0), (49, 49)).unwrap().cost, 315);
+    }
+
+    #[test]
+    fn test_wrapped_and_walls() {
+        let m = vec![vec![1, 9, 9, 1]];
+        let wrapped = Wrapped(Grid::new(&m));
+        let route = astar(&wrapped, (0, 0), (3, 0)).unwrap();
+        assert_eq!(route, Route { cost: 1, path: vec![(0, 0), (3, 0)] });
+
+        let walled = CostFn { width: 3, height: 1, max: 1, f: |p: Point| (p.0 != 1).then_some(1) };
+        assert_eq!(dijkstra(&walled, (0, 0), (2, 0)), None);
+        assert_eq!(dial(&walled, (0, 0), (2, 0)), None);
+
+        let empty:Matrix = vec![];
+        assert_eq!(Wrapped(Grid::new(&empty)).neighbors((0, 0)), [None; 4]);
+        assert_eq!(Grid::new(&empty).corner(), None);
+        assert_eq!(Tiled::new(&vec![vec![1, 2, 3]], 2).corner(), Some((5, 1)));
+    }
+
+    #[test]
+    fn test_zero_risk() {
+        let m = vec![vec![0, 9], vec![8, 1]];
+        let tiled = Tiled::new(&m, 2);
+        let costs:Vec<Option<usize>> = (0..4).map(|x| tiled.cost((x, 0))).collect();
+        assert_eq!(costs, vec![Some(0), Some(9), Some(1), Some(1)]);
+        assert_eq!(tiled.cost((2, 2)), Some(2));
+        assert_eq!(dial(&tiled, (0, 0), (3, 3)).unwrap().cost, dijkstra(&tiled, (0, 0), (3, 3)).unwrap().cost);
+    }
+
+    #[test]
+    #[should_panic(expected = "max_cost")]
+    fn test_dial_max_cost() {
+        let lying = CostFn { width: 3, height: 1, max: 2, f: |_: Point| Some(5) };
+        dial(&lying, (0, 0), (2, 0));
+    }
+}
@@ -0,0 +1,195 @@
+extern crate regex;
+
+use regex::Regex;
+use LineType::*;
+
+pub mod vent;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32
+}
+
+impl Point {
+    pub fn new(x: i32, y:i32) -> Point{
+        Point {x, y}
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LineType {
+    Horizontal,
+    Vertical,
+    Diagonal(i32),
+    Other
+}
+
+#[derive(Debug)]
+pub struct Line {
+    pub p1: Point,
+    pub p2: Point,
+    pub direction: LineType
+}
+
+impl Line {
+    pub fn new(mut p:Vec<Point>)  -> Line{
+        p.sort_by_key(|p| (p.x, p.y));
+        let p1 = p[0];
+        let p2 = p[1];
+
+        let direction = match (p1.x - p2.x, p2.y - p1.y) {
+            (_, 0) => Horizontal,
+            (0, _) => Vertical,
+            (m, n) if m.abs() == n.abs()  => Diagonal(n.signum()),
+            _ => Other
+        };
+        Line {p1, p2, direction}
+    }
+
+    pub fn points(&self) -> Vec<Point>{
+        // No negative step_by on Rust ranges ... wtf?
+        match self.direction {
+            Horizontal  => (self.p1.x..self.p2.x+1).map(|x| Point::new(x, self.p1.y)).collect(),
+            Vertical    => (self.p1.y..self.p2.y+1).map(|y| Point::new(self.p1.x, y)).collect(),
+            Diagonal(m) => (self.p1.x..self.p2.x+1)
+                            .enumerate()
+                            .map(|(i, x)| Point::new(x, self.p1.y + (i as i32 * m)))
+                            .collect(),
+            Other       => self.bresenham()
+        }
+    }
+
+    // Any other slope gets the nearest grid points, one per step along the longer axis
+    fn bresenham(&self) -> Vec<Point> {
+        let dx = (self.p2.x - self.p1.x).abs();
+        let dy = -(self.p2.y - self.p1.y).abs();
+        let (sx, sy) = ((self.p2.x - self.p1.x).signum(), (self.p2.y - self.p1.y).signum());
+        let mut err = dx + dy;
+        let mut p = self.p1;
+        let mut points = vec![p];
+        while p != self.p2 {
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                p.x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                p.y += sy;
+            }
+            points.push(p);
+        }
+        points
+    }
+}
+
+pub fn get_data(data:&str) -> Vec<Point>{
+    let re = Regex::new(r"(\d+),(\d+)").unwrap();
+    re.captures_iter(data)
+        .map(|n| Point::new(
+            n[1].parse::<i32>().unwrap(),
+            n[2].parse::<i32>().unwrap() )
+        )
+        .collect::<Vec<Point>>()
+}
+
+pub fn solution_one(data: &[Line]) -> usize {
+    let straight = data.iter().filter(|line| matches!(line.direction, Horizontal | Vertical));
+    vent::Vents::new(straight).count_overlaps()
+}
+
+pub fn solution_two(data: &[Line]) -> usize {
+    vent::Vents::new(data).count_overlaps()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn test_get_points(){
+        let horiz_points = vec![Point {x:0,y:0}, Point {x:0,y:1}, Point {x:0,y:2}];
+        let line:Line = Line::new(vec![Point::new(0, 0), Point::new(0, 2)]);
+        assert_eq!(line.points(), horiz_points);
+
+        let line:Line = Line::new(vec![Point::new(0, 2), Point::new(0, 0)]);
+        assert_eq!(line.points(), horiz_points);
+
+        let vert_points = vec![Point {x:3,y:2}, Point {x:4,y:2}, Point {x:5,y:2}];
+        let line:Line = Line::new(vec![Point::new(3, 2), Point::new(5, 2)]);
+        assert_eq!(line.points(), vert_points);
+
+        let line:Line = Line::new(vec![Point::new(5, 2), Point::new(3, 2)]);
+        assert_eq!(line.points(), vert_points);
+    }
+    #[test]
+    fn test_get_diag_points(){
+        let diag_points = vec![Point {x:0,y:0}, Point {x:1,y:1}, Point {x:2,y:2}];
+        let line:Line = Line::new(vec![Point::new(2, 2), Point::new(0, 0)]);
+        assert_eq!(line.points(), diag_points);
+
+        let line:Line = Line::new(vec![Point::new(0, 0), Point::new(2, 2)]);
+        assert_eq!(line.points(), diag_points);
+
+        let diag_points = vec![Point {x:0,y:2}, Point {x:1,y:1}, Point {x:2,y:0}];
+        let line:Line = Line::new(vec![Point::new(0, 2), Point::new(2, 0)]);
+
+        assert_eq!(line.points(), diag_points);
+    }
+    #[test]
+    fn test_get_other_points(){
+        let line:Line = Line::new(vec![Point::new(4, 2), Point::new(0, 0)]);
+        assert_eq!(line.direction, Other);
+        assert_eq!(line.points(), vec![
+            Point::new(0, 0), Point::new(1, 1), Point::new(2, 1), Point::new(3, 2), Point::new(4, 2)
+        ]);
+
+        let line:Line = Line::new(vec![Point::new(0, 5), Point::new(1, 0)]);
+        let points = line.points();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points.last(), Some(&Point::new(1, 0)));
+    }
+    #[test]
+    fn parse_input_string_problem_1(){
+        let s = "0,9 -> 5,9
+        8,0 -> 0,8
+        9,4 -> 3,4
+        2,2 -> 2,1
+        7,0 -> 7,4
+        6,4 -> 2,0
+        0,9 -> 2,9
+        3,4 -> 1,4
+        0,0 -> 8,8
+        5,5 -> 8,2";
+
+        let data = get_data(s);
+        assert_eq!(data.len(), 20);
+        assert_eq!(data[1], Point::new(5, 9));
+        assert_eq!(data[19], Point::new(8, 2));
+        let lines:Vec<Line> = data.chunks(2).map(|ps| Line::new(ps.to_vec())).collect();
+        let solution1 = solution_one(&lines);
+        assert_eq!(solution1, 5)
+    }
+    #[test]
+    fn parse_input_string_problem_2(){
+        let s = "0,9 -> 5,9
+        8,0 -> 0,8
+        9,4 -> 3,4
+        2,2 -> 2,1
+        7,0 -> 7,4
+        6,4 -> 2,0
+        0,9 -> 2,9
+        3,4 -> 1,4
+        0,0 -> 8,8
+        5,5 -> 8,2";
+
+        let data = get_data(s);
+        assert_eq!(data.len(), 20);
+        assert_eq!(data[1], Point::new(5, 9));
+        assert_eq!(data[19], Point::new(8, 2));
+
+        let lines:Vec<Line> = data.chunks(2).map(|ps| Line::new(ps.to_vec())).collect();
+        let solution2 = solution_two(&lines);
+        assert_eq!(solution2, 12)
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{HashMap, HashSet};
+use crate::{Line, LineType, Point};
+
+/// The four directions we can count without listing points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Family {
+    Horizontal,
+    Vertical,
+    // y = x + key
+    Rising,
+    // y = key - x
+    Falling
+}
+
+const FAMILIES: [Family; 4] = [Family::Horizontal, Family::Vertical, Family::Rising, Family::Falling];
+
+impl Family {
+    // Which line of this family a point is on and how far along it is
+    fn locate(&self, p: Point) -> (i32, i32) {
+        match self {
+            Family::Horizontal => (p.y, p.x),
+            Family::Vertical => (p.x, p.y),
+            Family::Rising => (p.y - p.x, p.x),
+            Family::Falling => (p.y + p.x, p.x)
+        }
+    }
+
+    fn point(&self, key: i32, t: i32) -> Point {
+        match self {
+            Family::Horizontal => Point::new(t, key),
+            Family::Vertical => Point::new(key, t),
+            Family::Rising => Point::new(t, t + key),
+            Family::Falling => Point::new(t, key - t)
+        }
+    }
+}
+
+// A stretch of one line covered by the same number of vents
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    start: i32,
+    end: i32,
+    count: usize
+}
+
+// As much of one line as is covered without a gap
+#[derive(Debug)]
+struct Span {
+    family: Family,
+    key: i32,
+    start: i32,
+    end: i32
+}
+
+impl Span {
+    fn contains(&self, p: Point) -> bool {
+        let (key, t) = self.family.locate(p);
+        key == self.key && self.start <= t && t <= self.end
+    }
+
+    fn x_range(&self) -> (i32, i32) {
+        match self.family {
+            Family::Vertical => (self.key, self.key),
+            _ => (self.start, self.end)
+        }
+    }
+}
+
+// Where two spans from different families cross, if they do on a grid point
+fn crossing(a: &Span, b: &Span) -> Option<Point> {
+    use Family::*;
+    let (a, b) = if a.family <= b.family { (a, b) } else { (b, a) };
+    let p = match (a.family, b.family) {
+        (Horizontal, Vertical) => Point::new(b.key, a.key),
+        (Horizontal, Rising) => Point::new(a.key - b.key, a.key),
+        (Horizontal, Falling) => Point::new(b.key - a.key, a.key),
+        (Vertical, Rising) => Point::new(a.key, a.key + b.key),
+        (Vertical, Falling) => Point::new(a.key, b.key - a.key),
+        (Rising, Falling) => {
+            // the diagonals can cross between grid points
+            if (b.key - a.key) % 2 != 0 {
+                return None
+            }
+            let x = (b.key - a.key) / 2;
+            Point::new(x, x + a.key)
+        },
+        _ => return None
+    };
+    (a.contains(p) && b.contains(p)).then_some(p)
+}
+
+// Sweeps the starts and ends of the intervals on one line into runs of equal cover
+fn to_runs(intervals: &[(i32, i32)]) -> Vec<Run> {
+    let mut events:Vec<(i32, i64)> = intervals.iter().flat_map(|&(a, b)| [(a, 1), (b + 1, -1)]).collect();
+    events.sort();
+    let mut runs = vec![];
+    let mut count = 0;
+    for (i, &(t, change)) in events.iter().enumerate() {
+        count += change;
+        if let Some(&(next, _)) = events.get(i + 1) {
+            if count > 0 && next > t {
+                runs.push(Run { start: t, end: next - 1, count: count as usize });
+            }
+        }
+    }
+    runs
+}
+
+///
+/// Vent lines, counted without visiting every point on them.
+/// Horizontal, vertical and 45° vents are kept as runs along each line they sit on,
+/// so overlaps along a line are interval arithmetic and overlaps between
+/// directions are line crossings. Any other slope is rasterised.
+///
+#[derive(Debug)]
+pub struct Vents {
+    runs: HashMap<(Family, i32), Vec<Run>>,
+    raster: HashMap<Point, usize>
+}
+
+impl Vents {
+    pub fn new<'a>(lines: impl IntoIterator<Item = &'a Line>) -> Vents {
+        let mut intervals:HashMap<(Family, i32), Vec<(i32, i32)>> = HashMap::new();
+        let mut raster = HashMap::new();
+        for line in lines {
+            let family = match line.direction {
+                LineType::Horizontal => Family::Horizontal,
+                LineType::Vertical => Family::Vertical,
+                LineType::Diagonal(1) => Family::Rising,
+                LineType::Diagonal(_) => Family::Falling,
+                LineType::Other => {
+                    for p in line.points() {
+                        *raster.entry(p).or_insert(0) += 1;
+                    }
+                    continue
+                }
+            };
+            let (key, a) = family.locate(line.p1);
+            let (_, b) = family.locate(line.p2);
+            intervals.entry((family, key)).or_default().push((a.min(b), a.max(b)));
+        }
+        let runs = intervals.iter().map(|(&k, iv)| (k, to_runs(iv))).collect();
+        Vents { runs, raster }
+    }
+
+    // How many vents of one family cover p
+    fn cover(&self, family: Family, p: Point) -> usize {
+        let (key, t) = family.locate(p);
+        let Some(runs) = self.runs.get(&(family, key)) else {
+            return 0
+        };
+        let i = runs.partition_point(|r| r.end < t);
+        runs.get(i).filter(|r| r.start <= t).map_or(0, |r| r.count)
+    }
+
+    fn straight_cover(&self, p: Point) -> usize {
+        FAMILIES.iter().map(|&f| self.cover(f, p)).sum()
+    }
+
+    /// How many vents cover p.
+    pub fn coverage(&self, p: Point) -> usize {
+        self.straight_cover(p) + self.raster.get(&p).copied().unwrap_or(0)
+    }
+
+    fn spans(&self) -> Vec<Span> {
+        let mut spans:Vec<Span> = vec![];
+        for (&(family, key), runs) in &self.runs {
+            for run in runs {
+                match spans.last_mut() {
+                    Some(s) if s.family == family && s.key == key && s.end + 1 == run.start => s.end = run.end,
+                    _ => spans.push(Span { family, key, start: run.start, end: run.end })
+                }
+            }
+        }
+        spans
+    }
+
+    // Sweeps left to right keeping the spans whose x range is still open,
+    // and checks each new span against those from other families
+    fn crossings(&self) -> HashSet<Point> {
+        let mut spans = self.spans();
+        spans.sort_by_key(|s| s.x_range());
+        let mut active:Vec<&Span> = vec![];
+        let mut crossings = HashSet::new();
+        for span in &spans {
+            let (left, _) = span.x_range();
+            active.retain(|s| s.x_range().1 >= left);
+            crossings.extend(active.iter()
+                .filter(|s| s.family != span.family)
+                .filter_map(|s| crossing(s, span)));
+            active.push(span);
+        }
+        crossings
+    }
+
+    /// How many points are covered by at least two vents.
+    pub fn count_overlaps(&self) -> usize {
+        // Points doubled up within one family are just run lengths.
+        let doubled:i64 = self.runs.values()
+            .flatten()
+            .filter(|r| r.count >= 2)
+            .map(|r| (r.end - r.start + 1) as i64)
+            .sum();
+
+        // Every point covered by two families is a crossing. Each should count once,
+        // but it's already been counted once for every family that doubles up there.
+        let crossed:i64 = self.crossings().into_iter()
+            .map(|p| 1 - FAMILIES.iter().filter(|&&f| self.cover(f, p) >= 2).count() as i64)
+            .sum();
+
+        // Rasterised points only add the ones that weren't overlaps already
+        let rastered = self.raster.iter()
+            .filter(|(&p, &n)| {
+                let straight = self.straight_cover(p);
+                straight < 2 && straight + n >= 2
+            })
+            .count();
+
+        (doubled + crossed) as usize + rastered
+    }
+
+    /// The smallest and largest corners of a box around every vent.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let spans = self.spans();
+        let ends = spans.iter()
+            .flat_map(|s| [s.family.point(s.key, s.start), s.family.point(s.key, s.end)])
+            .chain(self.raster.keys().copied());
+        ends.fold(None, |b, p| match b {
+            None => Some((p, p)),
+            Some((lo, hi)) => Some((
+                Point::new(lo.x.min(p.x), lo.y.min(p.y)),
+                Point::new(hi.x.max(p.x), hi.y.max(p.y))
+            ))
+        })
+    }
+
+    /// A plain (P2) PGM image of the vent counts over the bounding box,
+    /// brightest where the most vents overlap.
+    pub fn to_pgm(&self) -> String {
+        let Some((lo, hi)) = self.bounds() else {
+            return "P2\n0 0\n1\n".to_string()
+        };
+        let rows:Vec<Vec<usize>> = (lo.y..=hi.y)
+            .map(|y| (lo.x..=hi.x).map(|x| self.coverage(Point::new(x, y))).collect())
+            .collect();
+        let max = rows.iter().flatten().copied().max().unwrap_or(0).max(1);
+
+        let mut pgm = format!("P2\n{} {}\n{}\n", hi.x - lo.x + 1, hi.y - lo.y + 1, max);
+        for row in rows {
+            let row:Vec<String> = row.iter().map(|n| n.to_string()).collect();
+            pgm.push_str(&row.join(" "));
+            pgm.push('\n');
+        }
+        pgm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(x1: i32, y1: i32, x2: i32, y2: i32) -> Line {
+        Line::new(vec![Point::new(x1, y1), Point::new(x2, y2)])
+    }
+
+    #[test]
+    fn test_crossings() {
+        // a star through (5, 5) with two vertical vents overlapping on 5,4..5,6
+        let lines = [
+            line(0, 5, 10, 5), line(5, 0, 5, 6), line(5, 4, 5, 10),
+            line(0, 0, 10, 10), line(0, 10, 10, 0),
+        ];
+        let vents = Vents::new(&lines);
+        assert_eq!(vents.coverage(Point::new(5, 5)), 5);
+        assert_eq!(vents.coverage(Point::new(5, 4)), 2);
+        assert_eq!(vents.count_overlaps(), 3);
+
+        // diagonals that pass between grid points don't cross
+        let lines = [line(0, 0, 3, 3), line(0, 1, 1, 0)];
+        assert_eq!(Vents::new(&lines).count_overlaps(), 0);
+    }
+
+    #[test]
+    fn test_against_points() {
+        let lines = [
+            // the same vent both ways round, and a single point on it
+            line(0, 0, 6, 0), line(6, 0, 0, 0), line(3, 0, 3, 0),
+            // vertical vents meeting end to end, then overlapping by one
+            line(2, 1, 2, 4), line(2, 4, 2, 7), line(2, 7, 2, 9),
+            // diagonals both ways through the same point, one running backwards
+            line(0, 2, 6, 8), line(6, 2, 0, 8), line(5, 7, 1, 3),
+            // skewed vents that get rasterised, over straight overlaps and each other
+            line(0, 0, 6, 3), line(0, 0, 6, 3), line(1, 9, 7, 6),
+            // far off and negative
+            line(-4, -4, -1, -1), line(-1, -4, -4, -1), line(-3, -2, -3, -2),
+        ];
+        let mut counts:HashMap<Point, usize> = HashMap::new();
+        for l in &lines {
+            for p in l.points() {
+                *counts.entry(p).or_insert(0) += 1;
+            }
+        }
+        let vents = Vents::new(&lines);
+        assert_eq!(vents.count_overlaps(), counts.values().filter(|&&n| n > 1).count());
+        assert!(counts.iter().all(|(&p, &n)| vents.coverage(p) == n));
+    }
+
+    #[test]
+    fn test_pgm() {
+        let lines = [line(1, 1, 3, 1), line(2, 0, 2, 2)];
+        let vents = Vents::new(&lines);
+        assert_eq!(vents.bounds(), Some((Point::new(1, 0), Point::new(3, 2))));
+        assert_eq!(vents.to_pgm(), "P2\n3 3\n2\n0 1 0\n1 2 1\n0 1 0\n");
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+use num::Integer;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BingoError {
+    NoNumbers,
+    // line number and the text that wouldn't parse
+    BadNumber(usize, String),
+    // line number where a board's rows stopped lining up
+    Ragged(usize),
+    // rows, columns and how many numbers there were to fill them
+    Shape(usize, usize, usize)
+}
+
+impl fmt::Display for BingoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BingoError::NoNumbers => write!(f, "no numbers to draw"),
+            BingoError::BadNumber(n, s) => write!(f, "line {}: '{}' is not a number", n, s),
+            BingoError::Ragged(n) => write!(f, "line {}: board rows are different lengths", n),
+            BingoError::Shape(rows, cols, n) => write!(f, "a {}x{} board can't be filled with {} numbers", rows, cols, n)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Square<T:Integer + Copy> {
+    // A Square on a board. It's either Open with a number
+    // or Marked (it's been called)
+    Open(T),
+    Marked
+}
+
+#[derive(Debug, Clone)]
+pub struct Board<T:Integer + Copy + Hash>  {
+    // The squares row by row, plus an index from each number to
+    // where it sits so a draw doesn't have to look at every square.
+    // Counting hits per row and column means a win is spotted
+    // as soon as a count reaches the line's length.
+    rows: usize,
+    cols: usize,
+    diagonals: bool,
+    squares: Vec<Square<T>>,
+    index: HashMap<T, Vec<usize>>,
+    row_hits: Vec<usize>,
+    col_hits: Vec<usize>,
+    diagonal_hits: [usize; 2],
+    won: bool
+}
+
+impl<T:Integer + Copy + Hash> Board<T> {
+    /// A classic 5x5 board without diagonals.
+    pub fn new(data: Vec<T>) -> Result<Self, BingoError> {
+        Board::with_shape(data, 5, 5, false)
+    }
+
+    /// A rows x cols board. Diagonals only count on square boards.
+    pub fn with_shape(data: Vec<T>, rows: usize, cols: usize, diagonals: bool) -> Result<Self, BingoError> {
+        if data.len() != rows * cols {
+            return Err(BingoError::Shape(rows, cols, data.len()))
+        }
+        let mut index:HashMap<T, Vec<usize>> = HashMap::new();
+        for (i, n) in data.iter().enumerate() {
+            index.entry(*n).or_default().push(i);
+        }
+        Ok(Board {
+            rows,
+            cols,
+            diagonals: diagonals && rows == cols,
+            squares: data.into_iter().map(|n| Square::Open(n)).collect(),
+            index,
+            row_hits: vec![0; rows],
+            col_hits: vec![0; cols],
+            diagonal_hits: [0, 0],
+            won: false
+        })
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Marks n if it's on the board. Returns true if this draw made the board win.
+    pub fn play(&mut self, n:T) -> bool {
+        let Some(cells) = self.index.remove(&n) else {
+            return false
+        };
+        let already_won = self.won;
+        for i in cells {
+            self.squares[i] = Square::Marked;
+            let (row, col) = (i / self.cols, i % self.cols);
+            self.row_hits[row] += 1;
+            self.col_hits[col] += 1;
+            let mut full = self.row_hits[row] == self.cols || self.col_hits[col] == self.rows;
+
+            if self.diagonals {
+                if row == col {
+                    self.diagonal_hits[0] += 1;
+                    full |= self.diagonal_hits[0] == self.rows;
+                }
+                if row + col == self.cols - 1 {
+                    self.diagonal_hits[1] += 1;
+                    full |= self.diagonal_hits[1] == self.rows;
+                }
+            }
+            self.won |= full;
+        }
+        self.won && !already_won
+    }
+
+    pub fn winning(&self) -> bool {
+        self.won
+    }
+
+    /// The square at `row`, `col` counting from 0, if it's on the board.
+    pub fn square(&self, row: usize, col: usize) -> Option<&Square<T>> {
+        (row < self.rows && col < self.cols).then(|| &self.squares[row * self.cols + col])
+    }
+
+    pub fn sum_unmarked(&self) -> T {
+        // Sum all still Open squares
+        self.squares.iter().fold(T::zero(), |sum, s| match s {
+            Square::Open(n) => sum + *n,
+            Square::Marked => sum
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Win<T> {
+    pub board: usize,
+    // position in the list of drawn numbers
+    pub draw: usize,
+    pub number: T,
+    pub score: T
+}
+
+#[derive(Debug, Clone)]
+pub struct Bingo<T:Integer + Copy + Hash> {
+    pub numbers: Vec<T>,
+    pub boards: Vec<Board<T>>
+}
+
+impl<T:Integer + Copy + Hash> Bingo<T> {
+    /// Draws every number and returns each board's win in the order they happened.
+    /// Boards that never win aren't included.
+    pub fn play_all(&mut self) -> Vec<Win<T>> {
+        let mut wins = vec![];
+        for (draw, &n) in self.numbers.iter().enumerate() {
+            for (i, board) in self.boards.iter_mut().enumerate() {
+                if board.play(n) {
+                    wins.push(Win { board: i, draw, number: n, score: board.sum_unmarked() * n });
+                }
+            }
+            if wins.len() == self.boards.len() {
+                break
+            }
+        }
+        wins
+    }
+
+    pub fn with_diagonals(mut self) -> Self {
+        for board in self.boards.iter_mut() {
+            board.diagonals = board.rows == board.cols;
+        }
+        self
+    }
+}
+
+impl<T:Integer + Copy + Hash + FromStr> FromStr for Bingo<T> {
+    type Err = BingoError;
+
+    /// The numbers on the first line, then boards separated by blank lines.
+    /// Boards can be any size as long as their rows match up.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |line: usize, n: &str| n.trim().parse().map_err(|_| BingoError::BadNumber(line, n.to_string()));
+        let mut lines = s.lines().enumerate();
+
+        let (_, first) = lines.next().ok_or(BingoError::NoNumbers)?;
+        let numbers = first.split(',')
+            .map(|n| parse(1, n))
+            .collect::<Result<Vec<T>, _>>()?;
+
+        let mut boards = vec![];
+        let mut data = vec![];
+        let mut rows = 0;
+        // chaining a blank line on the end finishes the last board
+        for (i, line) in lines.chain([(0, "")]) {
+            let row = line.split_whitespace().map(|n| parse(i + 1, n)).collect::<Result<Vec<T>, _>>()?;
+            if row.is_empty() {
+                if let Some(cols) = data.len().checked_div(rows) {
+                    boards.push(Board::with_shape(std::mem::take(&mut data), rows, cols, false)?);
+                    rows = 0;
+                }
+                continue
+            }
+            if rows > 0 && row.len() * rows != data.len() {
+                return Err(BingoError::Ragged(i + 1))
+            }
+            data.extend(row);
+            rows += 1;
+        }
+        Ok(Bingo { numbers, boards })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static EXAMPLE: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7";
+
+    #[test]
+    fn create() {
+        let b:Board<i32> = Board::new(vec![22,13,17,11,0,8,2,23,4,24,21,9,14,16,7,6,10,3,18,5,1,12,20,15,19]).unwrap();
+        assert_eq!(b.square(0, 0), Some(&Square::Open(22)));
+        assert_eq!(b.square(4, 4), Some(&Square::Open(19)));
+        assert_eq!(b.square(0, 5), None);
+    }
+    #[test]
+    fn play() {
+        let mut b:Board<i32> = Board::new(vec![22,13,17,11,0,8,2,23,4,24,21,9,14,16,7,6,10,3,18,5,1,12,20,15,19]).unwrap();
+        b.play(17);
+        b.play(19);
+        assert_eq!(b.square(0, 2), Some(&Square::Marked));
+        assert_eq!(b.square(4, 4), Some(&Square::Marked));
+        assert_eq!(b.square(0, 1), Some(&Square::Open(13)));
+    }
+    #[test]
+    fn win_row() {
+        let mut b:Board<i32> = Board::new(vec![22,13,17,11,0,8,2,23,4,24,21,9,14,16,7,6,10,3,18,5,1,12,20,15,19]).unwrap();
+        assert!(!b.winning());
+
+        b.play(21);
+        b.play(9);
+        b.play(14);
+        assert!(!b.winning());
+
+        b.play(16);
+        b.play(7);
+        assert!(b.winning());
+    }
+    #[test]
+    fn win_col() {
+        let mut b:Board<i32> = Board::new(vec![22,13,17,11,0,8,2,23,4,24,21,9,14,16,7,6,10,3,18,5,1,12,20,15,19]).unwrap();
+        assert!(!b.winning());
+
+        b.play(13);
+        b.play(2);
+        b.play(9);
+        assert!(!b.winning());
+
+        b.play(10);
+        b.play(12);
+        assert!(b.winning());
+    }
+    #[test]
+    fn sum_unmarked() {
+        let mut b:Board<i32> = Board::new(vec![22,13,17,11,0,8,2,23,4,24,21,9,14,16,7,6,10,3,18,5,1,12,20,15,19]).unwrap();
+        assert_eq!(b.sum_unmarked(), 300);
+        b.play(10);
+        assert_eq!(b.sum_unmarked(), 290);
+    }
+    #[test]
+    fn win_order() {
+        let mut game:Bingo<i32> = EXAMPLE.parse().unwrap();
+        assert_eq!(game.boards.len(), 3);
+        let wins = game.play_all();
+        assert_eq!(wins.iter().map(|w| w.board).collect::<Vec<_>>(), vec![2, 0, 1]);
+        assert_eq!(wins[0], Win { board: 2, draw: 11, number: 24, score: 4512 });
+        assert_eq!(wins[2], Win { board: 1, draw: 14, number: 13, score: 1924 });
+    }
+    #[test]
+    fn shapes_and_diagonals() {
+        let mut b:Board<i32> = Board::with_shape(vec![1, 2, 3, 4, 5, 6], 2, 3, false).unwrap();
+        assert!(!b.play(1));
+        assert!(b.play(4));
+        // only the first winning draw counts
+        assert!(!b.play(2));
+
+        let mut b:Board<i32> = Board::with_shape(vec![1, 2, 3, 4, 5, 6, 7, 8, 9], 3, 3, true).unwrap();
+        b.play(3);
+        b.play(5);
+        assert!(b.play(7));
+
+        let game:Bingo<i32> = "1,2\n\n1 2 3\n4 5 6\n\n1 2\n3 4\n5 6".parse().unwrap();
+        assert_eq!(game.boards[0].shape(), (2, 3));
+        assert_eq!(game.boards[1].shape(), (3, 2));
+    }
+    #[test]
+    fn bad_input() {
+        assert_eq!("1,x\n".parse::<Bingo<i32>>().unwrap_err(), BingoError::BadNumber(1, "x".to_string()));
+        assert_eq!("1\n\n1 2\n3".parse::<Bingo<i32>>().unwrap_err(), BingoError::Ragged(4));
+        assert_eq!(Board::with_shape(vec![1, 2, 3], 2, 2, false).unwrap_err(), BingoError::Shape(2, 2, 3));
+        assert_eq!(Board::new(vec![1; 24]).unwrap_err(), BingoError::Shape(5, 5, 24));
+    }
+}
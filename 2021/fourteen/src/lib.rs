@@ -1,75 +1,226 @@
-use std::str;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Sub;
+use std::str::FromStr;
+use num::{CheckedAdd, CheckedMul, One, Zero};
 
-type Substitution = HashMap<String, (String, String)>;
-type Counter = HashMap<String, u64>;
+/// What pair counts are kept in. As well as checked adding and multiplying
+/// for the matrix power it has to be Ord, to pick out the most and least
+/// common letters at the end.
+pub trait Count: Clone + Ord + Zero + One + CheckedAdd + CheckedMul {}
+impl<T: Clone + Ord + Zero + One + CheckedAdd + CheckedMul> Count for T {}
 
-#[derive(Debug)]
-pub struct PolymerCounter {
-    pub current_counts: Counter,
-    pub substitutions: Substitution,
-    last_letter: u8
+type Matrix<T> = Vec<Vec<T>>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PolymerError {
+    EmptyTemplate,
+    BadRule(usize, String),
+    // a rule passed to Polymer::new that isn't a pair and one letter
+    InvalidRule(String),
+    Overflow
 }
 
-impl PolymerCounter {
-    pub fn new(template: &str, lookup: &HashMap<&str, &str>) -> Self {
-        let mut current_counts:HashMap<String, u64> = HashMap::new();
+impl fmt::Display for PolymerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolymerError::EmptyTemplate => write!(f, "the template is empty"),
+            PolymerError::BadRule(n, line) => write!(f, "line {}: can't read rule '{}'", n, line),
+            PolymerError::InvalidRule(rule) => write!(f, "'{}' isn't a pair and a letter to insert", rule),
+            PolymerError::Overflow => write!(f, "the counts are too big for this number type")
+        }
+    }
+}
 
-        for pair in template.as_bytes().windows(2){
-            let key = str::from_utf8(pair).unwrap().to_string();
-            *current_counts.entry(key).or_insert(0) += 1;
-        } 
+/// Pair insertion with every letter and pair interned to an index.
+/// Each step maps a pair to the two pairs either side of its inserted letter,
+/// which is a linear map, so step N is the pair counts times the
+/// transition matrix to the Nth power.
+#[derive(Debug)]
+pub struct Polymer {
+    pub letters: Vec<char>,
+    // pair index -> (first letter, second letter)
+    pub pairs: Vec<(usize, usize)>,
+    // pair index -> the pairs it becomes after one step
+    pub produces: Vec<Vec<usize>>,
+    template: Vec<usize>
+}
 
-        let mut substitutions = HashMap::new();
+impl Polymer {
+    pub fn new(template: &str, lookup: &HashMap<&str, &str>) -> Result<Self, PolymerError> {
+        let mut letters:Vec<char> = template.chars().collect();
+        let mut rules = vec![];
         for (key, value) in lookup {
-            let b = key.as_bytes();
-            let v = value.as_bytes();
-            let pair = (
-                str::from_utf8(&[b[0], v[0]]).unwrap().to_owned(),
-                str::from_utf8(&[v[0], b[1]]).unwrap().to_owned()
-            );
-
-            substitutions.insert(
-                key.to_string(),
-                pair
-            );
+            let pair:Vec<char> = key.chars().collect();
+            let insert:Vec<char> = value.chars().collect();
+            if pair.len() != 2 || insert.len() != 1 {
+                return Err(PolymerError::InvalidRule(format!("{} -> {}", key, value)))
+            }
+            letters.extend([pair[0], pair[1], insert[0]]);
+            rules.push((pair[0], pair[1], insert[0]));
+        }
+        letters.sort();
+        letters.dedup();
+
+        let letter = |c: char| letters.binary_search(&c).unwrap_or(0);
+        let k = letters.len();
+        let pair_index = |a: usize, b: usize| a * k + b;
+
+        // pairs without a rule just stay as they are
+        let pairs:Vec<(usize, usize)> = (0..k * k).map(|i| (i / k, i % k)).collect();
+        let mut produces:Vec<Vec<usize>> = (0..k * k).map(|i| vec![i]).collect();
+        for (a, b, c) in rules {
+            let (a, b, c) = (letter(a), letter(b), letter(c));
+            produces[pair_index(a, b)] = vec![pair_index(a, c), pair_index(c, b)];
+        }
+
+        let template:Vec<usize> = template.chars().map(letter).collect();
+        if template.is_empty() {
+            return Err(PolymerError::EmptyTemplate)
+        }
+        Ok(Polymer { letters, pairs, produces, template })
+    }
+
+    /// The one step transition matrix: entry [i][j] is how many of pair j
+    /// a single pair i turns into.
+    pub fn transition_matrix<T: Count>(&self) -> Matrix<T> {
+        let n = self.pairs.len();
+        let mut m = vec![vec![T::zero(); n]; n];
+        for (i, produced) in self.produces.iter().enumerate() {
+            for &j in produced {
+                m[i][j] = m[i][j].clone() + T::one();
+            }
         }
-        let last_letter = template.as_bytes().last().unwrap().clone();
-        Self {current_counts, substitutions, last_letter}
+        m
     }
 
-    fn increase_counts(&mut self) {
-        let mut counts:Counter = HashMap::new();
-        for (pair, count) in &self.current_counts {
-            let (p1, p2) = self.substitutions.get(pair).unwrap();
-            *counts.entry(p1.to_string()).or_default() += count;
-            *counts.entry(p2.to_string()).or_default() += count;
+    fn initial_counts<T: Count>(&self) -> Result<Vec<T>, PolymerError> {
+        let k = self.letters.len();
+        let mut counts = vec![T::zero(); self.pairs.len()];
+        for w in self.template.windows(2) {
+            let i = w[0] * k + w[1];
+            counts[i] = counts[i].checked_add(&T::one()).ok_or(PolymerError::Overflow)?;
         }
-        self.current_counts = counts;
+        Ok(counts)
     }
 
-    pub fn run_substitutions(&mut self, n: u8) {
-        for _ in 0..n {
-            self.increase_counts();
+    /// How many of each pair there are after `steps` steps. The matrix has a row
+    /// and column for each of the k² pairs of k letters, so this is O(k⁶ log steps).
+    pub fn pair_counts<T: Count>(&self, steps: u64) -> Result<Vec<T>, PolymerError> {
+        let mut counts = self.initial_counts()?;
+        let mut power = self.transition_matrix::<T>();
+        let mut n = steps;
+        while n > 0 {
+            if n & 1 == 1 {
+                counts = vec_mul(&counts, &power)?;
+            }
+            n >>= 1;
+            if n > 0 {
+                power = mat_mul(&power, &power)?;
+            }
         }
+        Ok(counts)
     }
-    pub fn count_letters(&self) -> u64 {
-        let mut counts: HashMap<u8, u64> = HashMap::new();
-        for (key, count) in &self.current_counts {
-            let bytes = key.as_bytes();
-            *counts.entry(bytes[0]).or_default() += count;
+
+    /// How many of every letter there are after `steps` steps.
+    pub fn histogram<T: Count>(&self, steps: u64) -> Result<BTreeMap<char, T>, PolymerError> {
+        let counts = self.pair_counts::<T>(steps)?;
+        let mut letters:Vec<T> = vec![T::zero(); self.letters.len()];
+
+        // every letter is the first of a pair except the very last one,
+        // which never changes
+        for (count, &(a, _)) in counts.iter().zip(&self.pairs) {
+            letters[a] = letters[a].checked_add(count).ok_or(PolymerError::Overflow)?;
         }
-        *counts.entry(self.last_letter).or_default() += 1;
-        let max = counts.values().max().unwrap();
-        let min = counts.values().min().unwrap();
-        max - min
+        let last = *self.template.last().ok_or(PolymerError::EmptyTemplate)?;
+        letters[last] = letters[last].checked_add(&T::one()).ok_or(PolymerError::Overflow)?;
+
+        Ok(self.letters.iter().copied()
+            .zip(letters)
+            .filter(|(_, n)| !n.is_zero())
+            .collect())
+    }
+
+    /// Most common letter count minus the least common after `steps` steps.
+    pub fn spread<T: Count + Sub<Output = T>>(&self, steps: u64) -> Result<T, PolymerError> {
+        let histogram = self.histogram::<T>(steps)?;
+        let max = histogram.values().max().cloned().unwrap_or_else(T::zero);
+        let min = histogram.values().min().cloned().unwrap_or_else(T::zero);
+        Ok(max - min)
+    }
+}
+
+impl FromStr for Polymer {
+    type Err = PolymerError;
 
+    /// The puzzle input: a template line, a blank line and then `AB -> C` rules.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let template = lines.next().ok_or(PolymerError::EmptyTemplate)?.trim();
+        let mut lookup = HashMap::new();
+
+        for (n, line) in lines.enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let (pair, insert) = line.trim()
+                .split_once(" -> ")
+                .filter(|(p, i)| p.chars().count() == 2 && i.chars().count() == 1)
+                .ok_or_else(|| PolymerError::BadRule(n + 2, line.to_string()))?;
+            lookup.insert(pair, insert);
+        }
+        Polymer::new(template, &lookup)
     }
 }
 
+fn dot<'a, T: Count + 'a>(a: impl Iterator<Item = &'a T>, b: impl Iterator<Item = &'a T>) -> Result<T, PolymerError> {
+    a.zip(b).try_fold(T::zero(), |acc, (x, y)| {
+        if x.is_zero() || y.is_zero() {
+            return Ok(acc)
+        }
+        acc.checked_add(&x.checked_mul(y).ok_or(PolymerError::Overflow)?).ok_or(PolymerError::Overflow)
+    })
+}
+
+fn vec_mul<T: Count>(v: &[T], m: &Matrix<T>) -> Result<Vec<T>, PolymerError> {
+    (0..m.len()).map(|j| dot(v.iter(), m.iter().map(|row| &row[j]))).collect()
+}
+
+fn mat_mul<T: Count>(a: &Matrix<T>, b: &Matrix<T>) -> Result<Matrix<T>, PolymerError> {
+    a.iter().map(|row| vec_mul(row, b)).collect()
+}
+
 #[cfg(test)]
 mod tests{
     use super::*;
+    use num::BigUint;
+
+    static EXAMPLE: &str = "NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
+    fn nonzero(polymer: &Polymer, counts: Vec<u64>) -> HashMap<String, u64> {
+        counts.into_iter().enumerate()
+            .filter(|(_, n)| *n > 0)
+            .map(|(i, n)| {
+                let (a, b) = polymer.pairs[i];
+                (format!("{}{}", polymer.letters[a], polymer.letters[b]), n)
+            })
+            .collect()
+    }
+
     #[test]
     fn test_new_counter_counts(){
         let template = "abc";
@@ -77,9 +228,9 @@ mod tests{
             ("ab", "c"),
             ("bc", "d")
         ]);
-        let counter = PolymerCounter::new(template, &lookup);
+        let polymer = Polymer::new(template, &lookup).unwrap();
         assert_eq!(
-            counter.current_counts,
+            nonzero(&polymer, polymer.pair_counts(0).unwrap()),
             HashMap::from([("ab".to_string(), 1), ("bc".to_string(), 1)])
         )
     }
@@ -90,14 +241,12 @@ mod tests{
             ("ab", "c"),
             ("bc", "d")
         ]);
-        let counter = PolymerCounter::new(template, &lookup);
-        assert_eq!(
-            counter.substitutions,
-            HashMap::from([
-                ("ab".to_string(), ("ac".to_string(), "cb".to_string())), 
-                ("bc".to_string(), ("bd".to_string(), "dc".to_string()))
-            ])
-        )
+        let polymer = Polymer::new(template, &lookup).unwrap();
+        // letters are a, b, c, d so pair "ab" is 0 * 4 + 1
+        assert_eq!(polymer.produces[1], vec![2, 9]);
+        assert_eq!(polymer.produces[6], vec![7, 14]);
+        // no rule for "aa"
+        assert_eq!(polymer.produces[0], vec![0]);
     }
 
     #[test]
@@ -107,14 +256,50 @@ mod tests{
             ("ab", "c"),
             ("bc", "c")
         ]);
-        let mut counter = PolymerCounter::new(template, &lookup);
-        counter.increase_counts();
+        let polymer = Polymer::new(template, &lookup).unwrap();
         assert_eq!(
-            counter.current_counts,
+            nonzero(&polymer, polymer.pair_counts(1).unwrap()),
             HashMap::from([
                 ("ac".to_string(), 1), ("cb".to_string(), 1),
                 ("bc".to_string(), 1), ("cc".to_string(), 1),
                 ])
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_example() {
+        let polymer:Polymer = EXAMPLE.parse().unwrap();
+        let h = polymer.histogram::<u64>(10).unwrap();
+        assert_eq!(h, BTreeMap::from([('B', 1749), ('C', 298), ('H', 161), ('N', 865)]));
+        assert_eq!(polymer.spread::<u64>(10), Ok(1588));
+        assert_eq!(polymer.spread::<u64>(40), Ok(2188189693529));
+    }
+
+    #[test]
+    fn test_missing_rules_are_stable() {
+        let polymer:Polymer = "NNCB\n\nNN -> C\n".parse().unwrap();
+        let h = polymer.histogram::<u64>(3).unwrap();
+        // NN splits once, after that no pair has a rule
+        assert_eq!(h, BTreeMap::from([('B', 1), ('C', 2), ('N', 2)]));
+    }
+
+    #[test]
+    fn test_big_steps() {
+        let polymer:Polymer = EXAMPLE.parse().unwrap();
+        assert_eq!(polymer.spread::<u64>(100), Err(PolymerError::Overflow));
+
+        // the polymer length doubles (less one) every step
+        let h = polymer.histogram::<BigUint>(2000).unwrap();
+        let total:BigUint = h.values().sum();
+        assert_eq!(total, BigUint::from(3u8) * (BigUint::one() << 2000usize) + BigUint::one());
+    }
+
+    #[test]
+    fn test_bad_rule() {
+        let err = "NNCB\n\nCH -> B\nCHB".parse::<Polymer>().unwrap_err();
+        assert_eq!(err, PolymerError::BadRule(4, "CHB".to_string()));
+
+        let lookup = HashMap::from([("CH", "B"), ("C", "N")]);
+        assert_eq!(Polymer::new("NNCB", &lookup).unwrap_err(), PolymerError::InvalidRule("C -> N".to_string()));
+    }
+}
@@ -9,9 +9,10 @@ use self::bencher::Bencher;
 
 use nine::make_matrix;
 use nine::solutions;
+use nine::basin::Basins;
 
 
-static PATH: &'static str = "./data.txt";
+static PATH: &str = "./data.txt";
 
 
 fn bench_test(b: &mut Bencher) {
@@ -22,8 +23,17 @@ fn bench_test(b: &mut Bencher) {
     b.iter(|| solutions(&matrix))
 }
 
+fn bench_basins(b: &mut Bencher) {
+    let path = Path::new(PATH);
+    let raw_data = fs::read_to_string(path).expect("You file was lost in the smoke");
+    let matrix = make_matrix(&raw_data);
+
+    b.iter(|| Basins::new(&matrix))
+}
+
 benchmark_group!(
     benches,
     bench_test,
+    bench_basins,
 );
 benchmark_main!(benches);
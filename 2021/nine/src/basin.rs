@@ -0,0 +1,265 @@
+use crate::Matrix;
+
+/// Cells are (x, y).
+pub type Point = (usize, usize);
+
+// Disjoint sets over cell indexes, with path halving and union by size
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind { parent: (0..n).collect(), size: vec![1; n] }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+
+    // Joins each cell to the one left of it and above it when `joined` says so
+    fn scan(width: usize, height: usize, joined: impl Fn(usize, usize) -> bool) -> Self {
+        let mut sets = UnionFind::new(width * height);
+        for i in 0..width * height {
+            if !i.is_multiple_of(width) && joined(i, i - 1) {
+                sets.union(i, i - 1);
+            }
+            if i >= width && joined(i, i - width) {
+                sets.union(i, i - width);
+            }
+        }
+        sets
+    }
+}
+
+/// A flat area lower than everything around it.
+/// A single cell is an ordinary low point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hollow {
+    pub height: u8,
+    pub cells: Vec<Point>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basin {
+    pub size: usize,
+    // the first of the lowest cells, reading left to right and top to bottom
+    pub lowest: Point,
+    pub floor: u8,
+    // how far the highest cell in the basin is above the floor
+    pub depth: u8,
+    pub hollows: Vec<Hollow>
+}
+
+impl Basin {
+    /// The cells strictly lower than all their neighbours.
+    pub fn low_points(&self) -> impl Iterator<Item = &Point> {
+        self.hollows.iter().filter(|h| h.cells.len() == 1).map(|h| &h.cells[0])
+    }
+
+    /// Flat hollows of more than one cell, which have no strict low point.
+    pub fn plateaus(&self) -> impl Iterator<Item = &Hollow> {
+        self.hollows.iter().filter(|h| h.cells.len() > 1)
+    }
+}
+
+///
+/// The basins of a height map. 9s are walls and every other cell belongs to
+/// the basin of the cells it's connected to, labelled in a single pass of
+/// union-find rather than a flood fill from each low point.
+///
+#[derive(Debug)]
+pub struct Basins {
+    pub width: usize,
+    pub height: usize,
+    // basin index for each cell, None for walls, by y * width + x
+    pub labels: Vec<Option<usize>>,
+    pub basins: Vec<Basin>
+}
+
+impl Basins {
+    pub fn new(matrix: &Matrix) -> Self {
+        let height = matrix.len();
+        let width = matrix.first().map_or(0, |r| r.len());
+        let cells:Vec<u8> = matrix.iter().flatten().copied().collect();
+
+        let mut basin_sets = UnionFind::scan(width, height, |a, b| cells[a] != 9 && cells[b] != 9);
+        // neighbours at the same height are one flat area
+        let mut flat_sets = UnionFind::scan(width, height, |a, b| cells[a] == cells[b]);
+
+        // number the basins in reading order
+        let mut labels = vec![None; cells.len()];
+        let mut basins:Vec<Basin> = vec![];
+        let mut label_of_root = vec![None; cells.len()];
+        for i in (0..cells.len()).filter(|&i| cells[i] != 9) {
+            let root = basin_sets.find(i);
+            let label = *label_of_root[root].get_or_insert_with(|| {
+                basins.push(Basin { size: 0, lowest: (i % width, i / width), floor: cells[i], depth: 0, hollows: vec![] });
+                basins.len() - 1
+            });
+            labels[i] = Some(label);
+            let basin = &mut basins[label];
+            basin.size += 1;
+            if cells[i] < basin.floor {
+                basin.floor = cells[i];
+                basin.lowest = (i % width, i / width);
+            }
+            basin.depth = basin.depth.max(cells[i]);
+        }
+        for basin in basins.iter_mut() {
+            basin.depth -= basin.floor;
+        }
+
+        // a flat area is a hollow if nothing next to it is lower
+        let mut hollow_of_root = vec![None; cells.len()];
+        let mut hollows:Vec<(usize, Hollow)> = vec![];
+        let neighbors = |i: usize| {
+            [
+                (!i.is_multiple_of(width)).then(|| i - 1),
+                (i % width + 1 < width).then(|| i + 1),
+                (i >= width).then(|| i - width),
+                (i + width < cells.len()).then(|| i + width)
+            ].into_iter().flatten()
+        };
+        let mut not_hollow = vec![false; cells.len()];
+        for i in (0..cells.len()).filter(|&i| cells[i] != 9) {
+            if neighbors(i).any(|n| cells[n] < cells[i]) {
+                not_hollow[flat_sets.find(i)] = true;
+            }
+        }
+        for i in (0..cells.len()).filter(|&i| cells[i] != 9) {
+            let root = flat_sets.find(i);
+            if not_hollow[root] {
+                continue
+            }
+            let index = *hollow_of_root[root].get_or_insert_with(|| {
+                hollows.push((labels[i].unwrap(), Hollow { height: cells[i], cells: vec![] }));
+                hollows.len() - 1
+            });
+            hollows[index].1.cells.push((i % width, i / width));
+        }
+        for (label, hollow) in hollows {
+            basins[label].hollows.push(hollow);
+        }
+
+        Basins { width, height, labels, basins }
+    }
+
+    pub fn label(&self, p: Point) -> Option<usize> {
+        self.labels[p.1 * self.width + p.0]
+    }
+
+    /// Basin sizes, biggest first.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes:Vec<usize> = self.basins.iter().map(|b| b.size).collect();
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    ///
+    /// A plain (P3) PPM image with each basin in its own colour, darker
+    /// where it's deeper, and the walls in black.
+    ///
+    pub fn to_ppm(&self, matrix: &Matrix) -> String {
+        let mut ppm = format!("P3\n{} {}\n255\n", self.width, self.height);
+        for (y, row) in matrix.iter().enumerate() {
+            let pixels:Vec<String> = row.iter().enumerate()
+                .map(|(x, &h)| match self.label((x, y)) {
+                    None => "0 0 0".to_string(),
+                    Some(label) => {
+                        let [r, g, b] = colour(label);
+                        let shade = |c: u32| c * (4 + h as u32) / 12;
+                        format!("{} {} {}", shade(r), shade(g), shade(b))
+                    }
+                })
+                .collect();
+            ppm.push_str(&pixels.join("  "));
+            ppm.push('\n');
+        }
+        ppm
+    }
+}
+
+// Spreads labels around the colour wheel so neighbouring basins differ
+fn colour(label: usize) -> [u32; 3] {
+    // golden angle steps never line up with each other
+    let hue = (label as f64 * 137.508) % 360.0;
+    let x = 1.0 - ((hue / 60.0) % 2.0 - 1.0).abs();
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x)
+    };
+    [(r * 255.0) as u32, (g * 255.0) as u32, (b * 255.0) as u32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::make_matrix;
+
+    static EXAMPLE: &str = "2199943210\n3987894921\n9856789892\n8767896789\n9899965678";
+
+    #[test]
+    fn test_labels() {
+        let m = make_matrix(EXAMPLE);
+        let basins = Basins::new(&m);
+        assert_eq!(basins.basins.len(), 4);
+        assert_eq!(basins.sizes(), vec![14, 9, 9, 3]);
+        assert_eq!(basins.label((0, 0)), Some(0));
+        assert_eq!(basins.label((2, 0)), None);
+        assert_eq!(basins.label((9, 0)), Some(1));
+
+        let top_left = &basins.basins[0];
+        assert_eq!(top_left.low_points().collect::<Vec<_>>(), vec![&(1, 0)]);
+        assert_eq!((top_left.floor, top_left.depth), (1, 2));
+        let low:Vec<Point> = basins.basins.iter().flat_map(|b| b.low_points().copied()).collect();
+        assert_eq!(low.len(), 4);
+    }
+
+    #[test]
+    fn test_plateaus() {
+        // the left basin bottoms out in a flat pair of 2s, the right has a low point
+        let m = make_matrix("32293\n52291\n99994");
+        let basins = Basins::new(&m);
+        assert_eq!(basins.basins.len(), 2);
+        let left = &basins.basins[0];
+        assert_eq!(left.low_points().count(), 0);
+        assert_eq!(
+            left.plateaus().collect::<Vec<_>>(),
+            vec![&Hollow { height: 2, cells: vec![(1, 0), (2, 0), (1, 1), (2, 1)] }]
+        );
+        assert_eq!(left.lowest, (1, 0));
+        assert_eq!(basins.basins[1].low_points().collect::<Vec<_>>(), vec![&(4, 1)]);
+    }
+
+    #[test]
+    fn test_ppm() {
+        let m = make_matrix("19\n91");
+        let ppm = Basins::new(&m).to_ppm(&m);
+        let lines:Vec<&str> = ppm.lines().collect();
+        assert_eq!(lines[..3], ["P3", "2 2", "255"]);
+        assert!(lines[3].ends_with("0 0 0"));
+        assert_ne!(colour(0), colour(1));
+    }
+}
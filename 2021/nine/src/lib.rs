@@ -1,37 +1,8 @@
-use std::collections::HashSet;
-use std::collections::BinaryHeap;
-use rayon::prelude::*;
+use basin::Basins;
 
-pub type Matrix = Vec<Vec<u8>>;
-
-#[derive(PartialEq, Eq, Hash, Clone)]
-struct Point {
-    x: usize,
-    y: usize
-}
+pub mod basin;
 
-impl Point {
-    fn new(x:usize, y:usize) -> Self {
-        Point {x, y}
-    }
-}
-
-fn get_neighbors(p: &Point, height:usize, width:usize) -> Vec<Point> {
-    let mut n = Vec::new();
-    if p.y > 0 {
-        n.push(Point::new(p.x, p.y - 1));
-    }
-    if p.y < height - 1 {
-        n.push(Point::new(p.x, p.y + 1));
-    }
-    if p.x > 0 {
-        n.push(Point::new(p.x - 1, p.y));
-    }
-    if p.x < width - 1 {
-        n.push(Point::new(p.x + 1, p.y));
-    } 
-    n
-}
+pub type Matrix = Vec<Vec<u8>>;
 
 pub fn make_matrix(data:&str) -> Matrix {
     data.lines()
@@ -41,67 +12,14 @@ pub fn make_matrix(data:&str) -> Matrix {
         .collect()
 }
 
-
-fn dfs(m: &Matrix, start: &Point) -> usize {
-    let h = m.len();
-    let w = m[0].len();
-
-    let mut count = 0;
-    let mut seen:HashSet<Point> = HashSet::new();
-    let mut stack:Vec<Point> = vec![start.clone()];
-
-
-    while !stack.is_empty(){
-        let p = stack.pop().unwrap();
-        if seen.contains(&p) || m[p.y][p.x] == 9 {
-            continue;
-        }
-        for n in get_neighbors(&p, h, w){
-            stack.push(n);
-        }
-        seen.insert(p);
-        count += 1;
-    }
-
-    count
-}
-
-fn low_points(line: &[u8], y:usize,  data: &Matrix) -> (usize, Vec<Point>){
-    let h = data.len();
-    let w = data[0].len();
-    let mut sum: usize = 0;
-    let mut points = Vec::new();
-
-    for (x, n) in line.iter().enumerate() {
-        let p = Point::new(x, y);
-        let smallest_neighbor = get_neighbors(&p, h, w)
-            .iter()
-            .map(|p| data[p.y][p.x])
-            .min()
-            .unwrap();
-
-        if n < &smallest_neighbor {
-            points.push(p);
-            sum += 1 + *n as usize
-        }
-    }
-
-    (sum, points)
-}
 pub fn solutions(matrix: &Matrix) -> (usize, usize) {
-    //use Rayon to run low-point search and DFS in threads.
-    let (sum, wells) = matrix
-        .par_iter()
-        .enumerate()
-        .map(|(idx, line)| low_points(line, idx, &matrix))
-        .reduce(|| (0, Vec::new()), |tup, (sum, wells)| (tup.0 + sum, [tup.1, wells].concat()));    
-    
-    let total:BinaryHeap<usize> = wells
-        .par_iter()
-        .map(|p| dfs(&matrix, p))
-        .collect();
+    let basins = Basins::new(matrix);
+    let sum = basins.basins.iter()
+        .flat_map(|b| b.low_points())
+        .map(|&(x, y)| 1 + matrix[y][x] as usize)
+        .sum();
 
-    let top = total.iter().take(3).fold(1, |a, n| a * n);
+    let top = basins.sizes().iter().take(3).product();
     (sum, top)
 }
 
@@ -123,13 +41,19 @@ mod tests{
         assert_eq!(m[4][9], 8);
     }
     #[test]
-    fn test_dfs() {
+    fn test_basin_size() {
         let s = get_matrix();
         let m = make_matrix(&s);
-        let start = Point {y:3, x:1};
-        
-        assert_eq!(dfs(&m, &start), 14);
-        let start = Point {y: 4, x: 9};
-        assert_eq!(dfs(&m, &start), 9);
+        let basins = Basins::new(&m);
+
+        let start = basins.label((1, 3)).unwrap();
+        assert_eq!(basins.basins[start].size, 14);
+        let start = basins.label((9, 4)).unwrap();
+        assert_eq!(basins.basins[start].size, 9);
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_solutions() {
+        let m = make_matrix(&get_matrix());
+        assert_eq!(solutions(&m), (15, 1134));
+    }
+}
@@ -1,9 +1,11 @@
+use std::env;
 use std::fs;
 use std::path::Path;
 use nine::make_matrix;
 use nine::solutions;
+use nine::basin::Basins;
 
-static PATH: &'static str = "./data.txt";
+static PATH: &str = "./data.txt";
 
 fn main() {
     let path = Path::new(PATH);
@@ -14,5 +16,9 @@ fn main() {
     println!("Solution 1: {:?}", solution_one);
     println!("Solution 2: {:?}", solution_two);
 
+    // pass a file name to get a map of the basins
+    if let Some(out) = env::args().nth(1) {
+        fs::write(&out, Basins::new(&matrix).to_ppm(&matrix)).expect("Can't write the basin map");
+        println!("basin map written to {}", out);
+    }
 }
-
@@ -1,54 +1,42 @@
-use std::path::Path;
+use std::env;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
-
-
-static PATH: &'static str = "./data.txt";
-
-fn read_integers<R: Read>(input: R) -> Vec<usize> {
-    let in_data = BufReader::new(input);
-    in_data.lines()
-        .map(|line| line.unwrap().parse().expect("The elves could not parse the integers!"))
-        .collect()
-}
-
-fn problem_one(nums:&Vec<usize>) -> usize {
-    nums.windows(2)
-        .filter(|pair| pair[0] < pair[1])
-        .count()
-}
-
-fn problem_two(nums:&Vec<usize>) -> usize {
-    let totals:Vec<usize> = nums.windows(3).map(|s| s.iter().sum()).collect();
-    totals.windows(2)
-        .filter(|pair| pair[0] < pair[1])
-        .count()}
+use std::io::{self, BufRead, BufReader};
+use one::sonar::analyse;
 
+static PATH: &str = "./data.txt";
 
 fn main(){
-    let path = Path::new(PATH);
-    let file = File::open(&path).expect("Error opening file, Merry X-Mas!");
-
-    let data = read_integers(file);
-    
-    let solution1 = problem_one(&data);
-    println!("solution one: {}", solution1); 
-
-    let solution2 = problem_two(&data);
-    println!("solution two: {}", solution2);
+    // a file name, or - to read from a pipe
+    let path = env::args().nth(1).unwrap_or_else(|| PATH.to_string());
+    let input:Box<dyn BufRead> = if path == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(&path).expect("Error opening file, Merry X-Mas!")))
+    };
+
+    let report = analyse(input, &[1, 3], 3).unwrap_or_else(|e| panic!("{}", e));
+
+    println!("solution one: {}", report.increases[0].1);
+    println!("solution two: {}", report.increases[1].1);
+    if let Some(run) = report.longest_run {
+        println!("longest descent: {} readings from reading {}", run.length, run.start + 1);
+    }
+    for jump in report.top_jumps {
+        println!("jump of {} at reading {}", jump.size, jump.index + 1);
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use one::sonar::count_increases;
     #[test]
     fn p_one(){
         let v = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
-        assert_eq!(problem_one(&v), 7);
+        assert_eq!(count_increases(v, 1), Ok(7));
     }
-#[test]
+    #[test]
     fn p_two(){
         let v = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
-        assert_eq!(problem_two(&v), 5);
+        assert_eq!(count_increases(v, 3), Ok(5));
     }
 }
@@ -0,0 +1,221 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::io::BufRead;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SonarError {
+    ZeroWindow,
+    BadReading(usize, String),
+    Io(String)
+}
+
+impl fmt::Display for SonarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SonarError::ZeroWindow => write!(f, "windows need at least one reading"),
+            SonarError::BadReading(n, line) => write!(f, "line {}: '{}' isn't a depth", n, line),
+            SonarError::Io(e) => write!(f, "couldn't read the sonar: {}", e)
+        }
+    }
+}
+
+/// A stretch of readings each deeper than the one before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    // index of the first reading
+    pub start: usize,
+    pub length: usize
+}
+
+/// How much deeper a reading was than the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump {
+    pub size: u64,
+    // index of the deeper reading
+    pub index: usize
+}
+
+// Bigger jumps rank higher, and of two the same size the earlier one does,
+// so the heap drops the later one and the report lists the earlier first.
+impl Ord for Jump {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.size.cmp(&other.size).then(other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Jump {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Two neighbouring k-windows share all but their first and last readings,
+// so the second sum is bigger exactly when the new reading beats the one
+// k back. Only the last k readings are kept, in a ring.
+#[derive(Debug)]
+struct WindowCounter {
+    ring: Vec<i64>,
+    size: usize,
+    seen: usize,
+    increases: usize
+}
+
+impl WindowCounter {
+    fn push(&mut self, depth: i64) {
+        let slot = self.seen % self.size;
+        if self.seen >= self.size {
+            if depth > self.ring[slot] {
+                self.increases += 1;
+            }
+            self.ring[slot] = depth;
+        } else {
+            self.ring.push(depth);
+        }
+        self.seen += 1;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Report {
+    pub readings: usize,
+    // window size and how many times its sum went up
+    pub increases: Vec<(usize, usize)>,
+    pub longest_run: Option<Run>,
+    // biggest first
+    pub top_jumps: Vec<Jump>
+}
+
+///
+/// Takes depth readings one at a time, keeping only what the windows
+/// and the top jumps need, so the input can be as long as you like.
+///
+#[derive(Debug)]
+pub struct Sonar {
+    windows: Vec<WindowCounter>,
+    top: usize,
+    jumps: BinaryHeap<Reverse<Jump>>,
+    previous: Option<i64>,
+    readings: usize,
+    run: Run,
+    longest: Option<Run>
+}
+
+impl Sonar {
+    /// Counts increases for each window size and keeps the `top` biggest jumps.
+    pub fn new(windows: &[usize], top: usize) -> Result<Self, SonarError> {
+        if windows.contains(&0) {
+            return Err(SonarError::ZeroWindow)
+        }
+        Ok(Sonar {
+            windows: windows.iter()
+                .map(|&size| WindowCounter { ring: Vec::with_capacity(size), size, seen: 0, increases: 0 })
+                .collect(),
+            top,
+            jumps: BinaryHeap::new(),
+            previous: None,
+            readings: 0,
+            run: Run { start: 0, length: 0 },
+            longest: None
+        })
+    }
+
+    pub fn push(&mut self, depth: i64) {
+        for w in self.windows.iter_mut() {
+            w.push(depth);
+        }
+        match self.previous {
+            Some(p) if depth > p => {
+                self.run.length += 1;
+                self.jumps.push(Reverse(Jump { size: depth.abs_diff(p), index: self.readings }));
+                if self.jumps.len() > self.top {
+                    self.jumps.pop();
+                }
+            },
+            _ => self.run = Run { start: self.readings, length: 1 }
+        }
+        if self.longest.is_none_or(|l| self.run.length > l.length) {
+            self.longest = Some(self.run);
+        }
+        self.previous = Some(depth);
+        self.readings += 1;
+    }
+
+    pub fn report(&self) -> Report {
+        let mut top_jumps:Vec<Jump> = self.jumps.iter().map(|Reverse(j)| *j).collect();
+        top_jumps.sort_by(|a, b| b.cmp(a));
+        Report {
+            readings: self.readings,
+            increases: self.windows.iter().map(|w| (w.size, w.increases)).collect(),
+            longest_run: self.longest,
+            top_jumps
+        }
+    }
+}
+
+/// Reads one depth per line from `reader` and reports on them.
+pub fn analyse<R: BufRead>(reader: R, windows: &[usize], top: usize) -> Result<Report, SonarError> {
+    let mut sonar = Sonar::new(windows, top)?;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| SonarError::Io(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue
+        }
+        let depth = line.trim().parse().map_err(|_| SonarError::BadReading(i + 1, line.clone()))?;
+        sonar.push(depth);
+    }
+    Ok(sonar.report())
+}
+
+/// How many times the sum of a `window` of readings is bigger than the one before.
+pub fn count_increases(depths: impl IntoIterator<Item = i64>, window: usize) -> Result<usize, SonarError> {
+    let mut sonar = Sonar::new(&[window], 0)?;
+    depths.into_iter().for_each(|d| sonar.push(d));
+    Ok(sonar.report().increases[0].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    static EXAMPLE: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+    #[test]
+    fn test_report() {
+        let report = analyse(Cursor::new(EXAMPLE), &[1, 3, 10], 2).unwrap();
+        assert_eq!(report.readings, 10);
+        assert_eq!(report.increases, vec![(1, 7), (3, 5), (10, 0)]);
+        assert_eq!(report.longest_run, Some(Run { start: 0, length: 4 }));
+        assert_eq!(report.top_jumps, vec![Jump { size: 33, index: 6 }, Jump { size: 29, index: 7 }]);
+    }
+
+    #[test]
+    fn test_tied_jumps() {
+        // three jumps of 5, only the first is kept
+        let report = analyse(Cursor::new("0\n5\n0\n5\n0\n5\n"), &[1], 1).unwrap();
+        assert_eq!(report.top_jumps, vec![Jump { size: 5, index: 1 }]);
+        let report = analyse(Cursor::new("0\n5\n0\n5\n2\n9\n"), &[1], 2).unwrap();
+        assert_eq!(report.top_jumps, vec![Jump { size: 7, index: 5 }, Jump { size: 5, index: 1 }]);
+    }
+
+    #[test]
+    fn test_against_windows() {
+        let depths:Vec<i64> = (0..200).map(|i: i64| (i * 7919) % 101 - i % 13).collect();
+        for k in 1..12 {
+            let sums:Vec<i64> = depths.windows(k).map(|w| w.iter().sum()).collect();
+            let expected = sums.windows(2).filter(|p| p[0] < p[1]).count();
+            assert_eq!(count_increases(depths.iter().copied(), k), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_bad_input() {
+        assert_eq!(analyse(Cursor::new("1\n\n2\ndeep"), &[1], 1), Err(SonarError::BadReading(4, "deep".to_string())));
+        assert_eq!(count_increases([1, 2], 0), Err(SonarError::ZeroWindow));
+        let extreme = analyse(Cursor::new(format!("{}\n{}", i64::MIN, i64::MAX)), &[1], 1).unwrap();
+        assert_eq!(extreme.top_jumps, vec![Jump { size: u64::MAX, index: 1 }]);
+        let empty = analyse(Cursor::new(""), &[1], 3).unwrap();
+        assert_eq!((empty.longest_run, empty.top_jumps), (None, vec![]));
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AlignError {
+    // position in the list (from 1) and what was there
+    BadPosition(usize, String)
+}
+
+impl fmt::Display for AlignError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlignError::BadPosition(n, s) => write!(f, "crab {}: '{}' isn't a position", n, s)
+        }
+    }
+}
+
+/// Reads the comma separated crab positions, in any order.
+pub fn parse_data(data: &str) -> Result<Vec<i64>, AlignError> {
+    data.trim()
+        .split(',')
+        .enumerate()
+        .map(|(i, n)| n.trim().parse().map_err(|_| AlignError::BadPosition(i + 1, n.to_string())))
+        .collect()
+}
+
+/// How much fuel a crab burns to move some distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fuel {
+    // one per step
+    Linear,
+    // each step costs one more than the last
+    Triangular,
+    // the distance squared
+    Quadratic
+}
+
+impl Fuel {
+    pub fn cost(&self, distance: u64) -> u64 {
+        match self {
+            Fuel::Linear => distance,
+            Fuel::Triangular => distance * (distance + 1) / 2,
+            Fuel::Quadratic => distance * distance
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: i64,
+    pub cost: u64
+}
+
+pub fn total_cost(crabs: &[i64], position: i64, cost: impl Fn(u64) -> u64) -> u64 {
+    crabs.iter().map(|&c| cost(c.abs_diff(position))).sum()
+}
+
+// The total is convex when every crab's cost is, so its slope never goes down.
+// Binary search for the first position where stepping right doesn't help,
+// which copes with flat stretches where a ternary search could stall.
+fn search(crabs: &[i64], mut lo: i64, mut hi: i64, cost: impl Fn(u64) -> u64) -> Alignment {
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if total_cost(crabs, mid + 1, &cost) >= total_cost(crabs, mid, &cost) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    Alignment { position: lo, cost: total_cost(crabs, lo, &cost) }
+}
+
+///
+/// The cheapest place to line up using any fuel function.
+/// The cost has to be convex and never go down as the distance grows,
+/// otherwise the answer is just a local minimum.
+/// Ties go to the leftmost position. Returns None when there are no crabs.
+///
+pub fn align_with(crabs: &[i64], cost: impl Fn(u64) -> u64) -> Option<Alignment> {
+    let lo = *crabs.iter().min()?;
+    let hi = *crabs.iter().max()?;
+    Some(search(crabs, lo, hi, cost))
+}
+
+///
+/// The cheapest place to line up with one of the standard fuel costs.
+/// These have known answers close by: the median for linear cost,
+/// the mean for quadratic and within half a step of the mean for triangular,
+/// so only a few positions are searched.
+///
+pub fn align(crabs: &[i64], fuel: Fuel) -> Option<Alignment> {
+    let lo = *crabs.iter().min()?;
+    let hi = *crabs.iter().max()?;
+    let n = crabs.len() as i64;
+    let (from, to) = match fuel {
+        Fuel::Linear => {
+            // every position between the two middle crabs is just as good
+            let mut sorted = crabs.to_vec();
+            let (_, &mut lower, _) = sorted.select_nth_unstable(((n - 1) / 2) as usize);
+            (lower, lower)
+        },
+        Fuel::Triangular | Fuel::Quadratic => {
+            let sum:i64 = crabs.iter().sum();
+            (sum.div_euclid(n) - 1, sum.div_euclid(n) + 1)
+        }
+    };
+    Some(search(crabs, from.max(lo), to.min(hi), |d| fuel.cost(d)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "16,1,2,0,4,2,7,1,2,14";
+
+    fn brute_force(crabs: &[i64], cost: impl Fn(u64) -> u64) -> Alignment {
+        let lo = *crabs.iter().min().unwrap();
+        let hi = *crabs.iter().max().unwrap();
+        let position = (lo..=hi).min_by_key(|&p| (total_cost(crabs, p, &cost), p)).unwrap();
+        Alignment { position, cost: total_cost(crabs, position, &cost) }
+    }
+
+    #[test]
+    fn test_example() {
+        let crabs = parse_data(EXAMPLE).unwrap();
+        assert_eq!(align(&crabs, Fuel::Linear), Some(Alignment { position: 2, cost: 37 }));
+        assert_eq!(align(&crabs, Fuel::Triangular), Some(Alignment { position: 5, cost: 168 }));
+        assert_eq!(align(&crabs, Fuel::Quadratic), Some(Alignment { position: 5, cost: 291 }));
+        assert_eq!(align_with(&crabs, |d| Fuel::Triangular.cost(d)), Some(Alignment { position: 5, cost: 168 }));
+        assert_eq!(align(&[], Fuel::Linear), None);
+        assert_eq!(parse_data("1,x"), Err(AlignError::BadPosition(2, "x".to_string())));
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let cases:[&[i64]; 8] = [
+            // one crab, and everyone already lined up
+            &[5], &[3, 3, 3, 3],
+            // an even count, so linear fuel is flat between the middle two
+            &[0, 10], &[1, 2, 8, 9],
+            // negatives, and one straggler miles from the rest
+            &[-7, -3, -3, 0, 2], &[0, 1, 1, 2, 500],
+            // the puzzle example, and the same crabs mirrored
+            &[16, 1, 2, 0, 4, 2, 7, 1, 2, 14], &[-14, -2, -7, -1, -2, -4, 0, -2, -1, -16],
+        ];
+        for crabs in cases {
+            for fuel in [Fuel::Linear, Fuel::Triangular, Fuel::Quadratic] {
+                assert_eq!(align(crabs, fuel).unwrap(), brute_force(crabs, |d| fuel.cost(d)));
+            }
+            // flat up to a distance of 10 then cubic
+            let custom = |d: u64| d.saturating_sub(10).pow(3);
+            assert_eq!(align_with(crabs, custom).unwrap(), brute_force(crabs, custom));
+        }
+    }
+}
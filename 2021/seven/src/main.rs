@@ -1,38 +1,16 @@
 use std::fs;
 use std::path::Path;
+use seven::alignment::{align, parse_data, Fuel};
 
-static PATH: &'static str = "./data.txt";
-
-fn parse_data(data:&str) -> Vec<i64> {
-    data.split(',').map(|n| n.parse().unwrap()).collect()
-}
-
-fn problem_one(data:&Vec<i64>) -> i64 {
-    let med = data[data.len() / 2];
-    data.iter().fold(0, |a, n| a + (n - med).abs())
-}
-
-fn tri_sum(n: i64) -> i64 {
-    (n * (n + 1)) / 2
-}
-
-fn p2_cost(pos: i64, data:&Vec<i64>) -> i64 {
-    data.iter().fold(0, |a, n| a + tri_sum((n-pos).abs()))
-}
-
-fn problem_two(data:&Vec<i64>) -> i64 {
-    let max_val = data.iter().max().unwrap();
-    (0..*max_val).map(|pos| p2_cost(pos, data)).min().unwrap()
-}
-
+static PATH: &str = "./data.txt";
 
 fn main() {
     let path = Path::new(PATH);
     let data = fs::read_to_string(path).expect("Crabs ran out of fuel!");
-    // sort for convenience
-    let mut data = parse_data(&data);
-    data.sort();
+    let crabs = parse_data(&data).unwrap_or_else(|e| panic!("{}", e));
 
-    println!("Solution 1: {:?}", problem_one(&data));
-    println!("Solution 2: {:?}", problem_two(&data));
+    let one = align(&crabs, Fuel::Linear).expect("No crabs!");
+    println!("Solution 1: {} (at {})", one.cost, one.position);
+    let two = align(&crabs, Fuel::Triangular).expect("No crabs!");
+    println!("Solution 2: {} (at {})", two.cost, two.position);
 }
@@ -0,0 +1,3 @@
+extern crate num;
+
+pub mod population;
@@ -1,29 +1,17 @@
 use std::path::Path;
 use std::fs;
-use std::collections::VecDeque;
+use six::population::{parse_timers, Lifecycle};
 
-static PATH: &'static str = "./data.txt";
+static PATH: &str = "./data.txt";
 
-fn parse_file(path: &str) -> Vec<usize> {
-    let path = Path::new(path);
+fn main() {
+    let path = Path::new(PATH);
     let data = fs::read_to_string(path).expect("The laternfish ate your data file");
-    data.trim().split(',').map(|n| n.parse().unwrap()).collect()
-}
+    let timers = parse_timers(&data).unwrap_or_else(|e| panic!("{}", e));
+    let fish = Lifecycle::default();
 
-fn count_fish(days: usize, data: &[usize]) -> i64 {
-    let mut counts: VecDeque<i64> = [0; 9].into();
-    for &index in data {
-        counts[index] += 1;
-    }
-    for _ in 0..days {
-        counts[7] += counts[0];
-        counts.rotate_left(1);
-    }
-    counts.iter().sum()
-}
-
-fn main() {
-    let numbers = parse_file(PATH);
-    println!("Solution1: {}", count_fish(80, &numbers));
-    println!("Solution1: {}", count_fish(256, &numbers));
+    let solution1 = fish.count::<u64>(&timers, 80).unwrap_or_else(|e| panic!("{}", e));
+    println!("Solution1: {}", solution1);
+    let solution2 = fish.count::<u64>(&timers, 256).unwrap_or_else(|e| panic!("{}", e));
+    println!("Solution2: {}", solution2);
 }
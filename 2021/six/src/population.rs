@@ -0,0 +1,220 @@
+use std::fmt;
+use num::{CheckedAdd, CheckedMul, One, Zero};
+
+/// The number type a school is tallied in. The population grows exponentially,
+/// so the arithmetic is checked and a fixed width type reports Overflow rather
+/// than wrapping; `num::BigUint` never does.
+pub trait Count: Clone + Zero + One + CheckedAdd + CheckedMul {}
+impl<T: Clone + Zero + One + CheckedAdd + CheckedMul> Count for T {}
+
+type Matrix<T> = Vec<Vec<T>>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PopulationError {
+    ZeroCycle,
+    ZeroModulus,
+    // position in the list (from 1) and what was there
+    BadTimer(usize, String),
+    TimerTooBig(usize),
+    Overflow
+}
+
+impl fmt::Display for PopulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PopulationError::ZeroCycle => write!(f, "the spawning cycle has to be at least one day"),
+            PopulationError::ZeroModulus => write!(f, "can't count modulo zero"),
+            PopulationError::BadTimer(n, s) => write!(f, "fish {}: '{}' isn't a timer", n, s),
+            PopulationError::TimerTooBig(t) => write!(f, "no fish has a timer of {}", t),
+            PopulationError::Overflow => write!(f, "too many fish for this number type")
+        }
+    }
+}
+
+/// Reads the comma separated timers from the puzzle input.
+pub fn parse_timers(s: &str) -> Result<Vec<usize>, PopulationError> {
+    s.trim()
+        .split(',')
+        .enumerate()
+        .map(|(i, n)| n.trim().parse().map_err(|_| PopulationError::BadTimer(i + 1, n.to_string())))
+        .collect()
+}
+
+///
+/// How lanternfish breed: a fish spawns every `cycle` days and a newborn
+/// waits an extra `delay` days before its first cycle. Fish are grouped by timer,
+/// so a day is a linear map on the age histogram (a Leslie matrix)
+/// and day N is that matrix to the Nth power.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lifecycle {
+    cycle: usize,
+    delay: usize
+}
+
+impl Default for Lifecycle {
+    /// Lanternfish as described: seven day cycle, two days extra for newborns.
+    fn default() -> Self {
+        Lifecycle { cycle: 7, delay: 2 }
+    }
+}
+
+impl Lifecycle {
+    pub fn new(cycle: usize, delay: usize) -> Result<Self, PopulationError> {
+        if cycle == 0 {
+            return Err(PopulationError::ZeroCycle)
+        }
+        Ok(Lifecycle { cycle, delay })
+    }
+
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+
+    /// How many timer values there are, 0 up to a newborn's.
+    pub fn ages(&self) -> usize {
+        self.cycle + self.delay
+    }
+
+    /// Entry [i][j] is how many fish with timer j one fish with timer i becomes in a day.
+    pub fn matrix<T: Count>(&self) -> Matrix<T> {
+        let n = self.ages();
+        let mut m = vec![vec![T::zero(); n]; n];
+        for (i, row) in m.iter_mut().enumerate().skip(1) {
+            row[i - 1] = T::one();
+        }
+        // a fish at 0 restarts its cycle and has a newborn
+        m[0][self.cycle - 1] = m[0][self.cycle - 1].clone() + T::one();
+        m[0][n - 1] = m[0][n - 1].clone() + T::one();
+        m
+    }
+
+    fn initial<T: Count>(&self, timers: &[usize]) -> Result<Vec<T>, PopulationError> {
+        let mut counts = vec![T::zero(); self.ages()];
+        for &t in timers {
+            let count = counts.get_mut(t).ok_or(PopulationError::TimerTooBig(t))?;
+            *count = count.checked_add(&T::one()).ok_or(PopulationError::Overflow)?;
+        }
+        Ok(counts)
+    }
+
+    /// How many fish have each timer value after `days` days.
+    pub fn histogram<T: Count>(&self, timers: &[usize], days: u64) -> Result<Vec<T>, PopulationError> {
+        let add = |a: &T, b: &T| a.checked_add(b).ok_or(PopulationError::Overflow);
+        let mul = |a: &T, b: &T| a.checked_mul(b).ok_or(PopulationError::Overflow);
+        power(self.initial(timers)?, self.matrix(), days, &add, &mul)
+    }
+
+    /// The histogram with every count taken modulo `modulus`,
+    /// for when there are far too many fish to count exactly.
+    pub fn histogram_mod(&self, timers: &[usize], days: u64, modulus: u64) -> Result<Vec<u64>, PopulationError> {
+        if modulus == 0 {
+            return Err(PopulationError::ZeroModulus)
+        }
+        let m = modulus as u128;
+        let add = |a: &u64, b: &u64| Ok(((*a as u128 + *b as u128) % m) as u64);
+        let mul = |a: &u64, b: &u64| Ok(((*a as u128 * *b as u128) % m) as u64);
+        let counts = self.initial::<u64>(timers)?.iter().map(|c| c % modulus).collect();
+        power(counts, self.matrix(), days, &add, &mul)
+    }
+
+    /// How many fish there are after `days` days.
+    pub fn count<T: Count>(&self, timers: &[usize], days: u64) -> Result<T, PopulationError> {
+        self.histogram::<T>(timers, days)?
+            .iter()
+            .try_fold(T::zero(), |sum, n| sum.checked_add(n).ok_or(PopulationError::Overflow))
+    }
+}
+
+type Op<'a, T> = &'a dyn Fn(&T, &T) -> Result<T, PopulationError>;
+
+fn vec_mul<T: Count>(v: &[T], m: &Matrix<T>, add: Op<T>, mul: Op<T>) -> Result<Vec<T>, PopulationError> {
+    (0..m.len())
+        .map(|j| {
+            v.iter().zip(m).try_fold(T::zero(), |acc, (x, row)| {
+                if x.is_zero() || row[j].is_zero() {
+                    return Ok(acc)
+                }
+                add(&acc, &mul(x, &row[j])?)
+            })
+        })
+        .collect()
+}
+
+// Square and multiply, so N days take O(k³ log N) for k timer values
+fn power<T: Count>(mut counts: Vec<T>, mut m: Matrix<T>, days: u64, add: Op<T>, mul: Op<T>) -> Result<Vec<T>, PopulationError> {
+    let mut n = days;
+    while n > 0 {
+        if n & 1 == 1 {
+            counts = vec_mul(&counts, &m, add, mul)?;
+        }
+        n >>= 1;
+        if n > 0 {
+            m = m.iter().map(|row| vec_mul(row, &m, add, mul)).collect::<Result<_, _>>()?;
+        }
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+
+    static EXAMPLE: &str = "3,4,3,1,2";
+
+    // One day at a time, the way the puzzle tells it
+    fn simulate(timers: &[usize], days: usize) -> Vec<u64> {
+        let mut counts = [0u64; 9];
+        for &t in timers {
+            counts[t] += 1;
+        }
+        for _ in 0..days {
+            counts[7] += counts[0];
+            counts.rotate_left(1);
+        }
+        counts.to_vec()
+    }
+
+    #[test]
+    fn test_example() {
+        let timers = parse_timers(EXAMPLE).unwrap();
+        let fish = Lifecycle::default();
+        assert_eq!(fish.histogram::<u64>(&timers, 18), Ok(vec![3, 5, 3, 2, 2, 1, 5, 1, 4]));
+        assert_eq!(fish.count::<u64>(&timers, 80), Ok(5934));
+        assert_eq!(fish.count::<u64>(&timers, 256), Ok(26984457539));
+        for days in [0, 1, 7, 9, 100, 200] {
+            assert_eq!(fish.histogram::<u64>(&timers, days as u64).unwrap(), simulate(&timers, days));
+        }
+    }
+
+    #[test]
+    fn test_big_and_modular() {
+        let timers = parse_timers(EXAMPLE).unwrap();
+        let fish = Lifecycle::default();
+        assert_eq!(fish.count::<u64>(&timers, 1000), Err(PopulationError::Overflow));
+
+        let modulus = 1_000_000_007u64;
+        let exact = fish.histogram::<BigUint>(&timers, 1000).unwrap();
+        let reduced:Vec<u64> = exact.iter().map(|n| (n % modulus).try_into().unwrap()).collect();
+        assert_eq!(fish.histogram_mod(&timers, 1000, modulus).unwrap(), reduced);
+
+        // a trillion days only takes forty squarings
+        assert_eq!(fish.histogram_mod(&timers, 1_000_000_000_000, modulus).unwrap().len(), 9);
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        // no delay and a three day cycle: a fish doubles every three days
+        let fish = Lifecycle::new(3, 0).unwrap();
+        assert_eq!(fish.histogram::<u64>(&[0], 10), Ok(vec![0, 0, 16]));
+        assert_eq!(Lifecycle::new(0, 2), Err(PopulationError::ZeroCycle));
+        assert_eq!((fish.cycle(), fish.delay()), (3, 0));
+        assert_eq!(fish.count::<u64>(&[5], 1), Err(PopulationError::TimerTooBig(5)));
+        assert_eq!(parse_timers("3,x"), Err(PopulationError::BadTimer(2, "x".to_string())));
+    }
+}
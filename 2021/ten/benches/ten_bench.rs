@@ -11,7 +11,7 @@ use ten::solution_one;
 use ten::solution_two;
 
 
-static PATH: &'static str = "./data.txt";
+static PATH: &str = "./data.txt";
 
 
 fn bench_test_one(b: &mut Bencher) {
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+use rayon::prelude::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GrammarError {
+    // a character used by more than one delimiter
+    Reused(char),
+    BadRule(usize, String)
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrammarError::Reused(c) => write!(f, "'{}' is used by more than one delimiter", c),
+            GrammarError::BadRule(n, line) => write!(f, "line {}: can't read delimiter '{}'", n, line)
+        }
+    }
+}
+
+/// A pair of delimiters and what they score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delimiter {
+    pub open: char,
+    pub close: char,
+    // for a corrupted line that finds this closer where it shouldn't be
+    pub corrupt_score: usize,
+    // for each of these closers needed to complete a line
+    pub complete_score: usize
+}
+
+/// What a line turned out to be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Complete,
+    // the closers that would finish the line, innermost first
+    Incomplete { completion: String },
+    // `expected` is the closer that should have been at `position`,
+    // None if nothing was open
+    Corrupted { position: usize, found: char, expected: Option<char> }
+}
+
+///
+/// The delimiters a navigation line can use and how they score.
+/// Completion scores are built up as `total * base + score` for each closer.
+///
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    pub delimiters: Vec<Delimiter>,
+    pub base: usize,
+    // character -> (index into delimiters, true if it opens)
+    lookup: HashMap<char, (usize, bool)>
+}
+
+impl Grammar {
+    pub fn new(delimiters: Vec<Delimiter>, base: usize) -> Result<Self, GrammarError> {
+        let mut lookup = HashMap::new();
+        for (i, d) in delimiters.iter().enumerate() {
+            for (c, open) in [(d.open, true), (d.close, false)] {
+                if lookup.insert(c, (i, open)).is_some() {
+                    return Err(GrammarError::Reused(c))
+                }
+            }
+        }
+        Ok(Grammar { delimiters, base, lookup })
+    }
+
+    /// The four pairs from the submarine's navigation subsystem.
+    pub fn navigation() -> Self {
+        "() 3 1\n[] 57 2\n{} 1197 3\n<> 25137 4".parse().unwrap()
+    }
+
+    pub fn classify(&self, line: &str) -> Status {
+        let mut open:Vec<usize> = vec![];
+        for (position, c) in line.chars().enumerate() {
+            match self.lookup.get(&c) {
+                Some(&(i, true)) => open.push(i),
+                Some(&(i, false)) if open.last() == Some(&i) => {
+                    open.pop();
+                },
+                _ => {
+                    let expected = open.last().map(|&i| self.delimiters[i].close);
+                    return Status::Corrupted { position, found: c, expected }
+                }
+            }
+        }
+        if open.is_empty() {
+            return Status::Complete
+        }
+        let completion = open.iter().rev().map(|&i| self.delimiters[i].close).collect();
+        Status::Incomplete { completion }
+    }
+
+    /// What finding `found` scores on a corrupted line.
+    pub fn corrupt_score(&self, found: char) -> usize {
+        match self.lookup.get(&found) {
+            Some(&(i, _)) => self.delimiters[i].corrupt_score,
+            None => 0
+        }
+    }
+
+    /// The score for a completion string.
+    pub fn completion_score(&self, completion: &str) -> usize {
+        completion.chars().fold(0, |total, c| {
+            let score = self.lookup.get(&c).map_or(0, |&(i, _)| self.delimiters[i].complete_score);
+            total * self.base + score
+        })
+    }
+
+    /// Classifies every line in parallel, keeping them in order.
+    pub fn classify_all(&self, s: &str) -> Vec<Status> {
+        s.par_lines().map(|line| self.classify(line)).collect()
+    }
+
+    ///
+    /// Classifies a reader too big to hold in memory, `chunk` lines at a time.
+    /// Each chunk is classified in parallel and handed to `f` in order
+    /// with its line number (from 1).
+    ///
+    pub fn classify_reader<R: BufRead>(&self, reader: R, chunk: usize, mut f: impl FnMut(usize, Status)) -> io::Result<()> {
+        let mut lines = reader.lines();
+        let mut line_number = 1;
+        loop {
+            let batch = lines.by_ref().take(chunk.max(1)).collect::<io::Result<Vec<String>>>()?;
+            if batch.is_empty() {
+                return Ok(())
+            }
+            let statuses:Vec<Status> = batch.par_iter().map(|line| self.classify(line)).collect();
+            for status in statuses {
+                f(line_number, status);
+                line_number += 1;
+            }
+        }
+    }
+}
+
+impl FromStr for Grammar {
+    type Err = GrammarError;
+
+    /// One pair per line: the opener and closer together, then the corrupt
+    /// and completion scores, like `() 3 1`. The completion base is 5.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut delimiters = vec![];
+        for (n, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let bad = || GrammarError::BadRule(n + 1, line.to_string());
+            let parts:Vec<&str> = line.split_whitespace().collect();
+            let [pair, corrupt, complete] = parts[..] else {
+                return Err(bad())
+            };
+            let pair:Vec<char> = pair.chars().collect();
+            let [open, close] = pair[..] else {
+                return Err(bad())
+            };
+            delimiters.push(Delimiter {
+                open,
+                close,
+                corrupt_score: corrupt.parse().map_err(|_| bad())?,
+                complete_score: complete.parse().map_err(|_| bad())?
+            });
+        }
+        Grammar::new(delimiters, 5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_classify() {
+        let grammar = Grammar::navigation();
+        assert_eq!(
+            grammar.classify("{([(<{}[<>[]}>{[]{[(<()>"),
+            Status::Corrupted { position: 12, found: '}', expected: Some(']') }
+        );
+        assert_eq!(
+            grammar.classify("[({(<(())[]>[[{[]{<()<>>"),
+            Status::Incomplete { completion: "}}]])})]".to_string() }
+        );
+        assert_eq!(grammar.classify("[()()<{}>]"), Status::Complete);
+        assert_eq!(grammar.classify("())"), Status::Corrupted { position: 2, found: ')', expected: None });
+        assert_eq!(grammar.completion_score("}}]])})]"), 288957);
+    }
+
+    #[test]
+    fn test_custom_grammar() {
+        let grammar:Grammar = "<> 1 1\nab 10 2".parse().unwrap();
+        assert_eq!(grammar.classify("a<"), Status::Incomplete { completion: ">b".to_string() });
+        assert_eq!(grammar.classify("a<>b"), Status::Complete);
+        assert_eq!(grammar.classify("a<b"), Status::Corrupted { position: 2, found: 'b', expected: Some('>') });
+        assert_eq!(grammar.corrupt_score('b'), 10);
+
+        assert_eq!("() 3 1\n(] 1 1".parse::<Grammar>().unwrap_err(), GrammarError::Reused('('));
+        assert_eq!("() 3".parse::<Grammar>().unwrap_err(), GrammarError::BadRule(1, "() 3".to_string()));
+    }
+
+    #[test]
+    fn test_classify_reader() {
+        let grammar = Grammar::navigation();
+        let input = "()\n(]\n((\n<>\n[";
+        let mut seen = vec![];
+        grammar.classify_reader(Cursor::new(input), 2, |n, status| seen.push((n, status))).unwrap();
+        assert_eq!(seen.len(), 5);
+        assert_eq!(seen[1], (2, Status::Corrupted { position: 1, found: ']', expected: Some(')') }));
+        assert_eq!(seen[4], (5, Status::Incomplete { completion: "]".to_string() }));
+        assert_eq!(seen.into_iter().map(|(_, s)| s).collect::<Vec<_>>(), grammar.classify_all(input));
+    }
+}
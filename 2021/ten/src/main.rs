@@ -12,6 +12,6 @@ fn main() {
     println!("solution one: {:?}", solution1);
 
     let solution2 = solution_two(&data);
-    println!("solution one: {:2}", solution2);
+    println!("solution two: {}", solution2);
 
 }
@@ -1,33 +1,13 @@
-
-///
-/// Parse input data into Points and tuples of char, int like: ('x', 7)
-/// 
-pub fn parse_data(data: &str) -> (Vec<Point>, Vec<(char, usize)>) {
-    let (points, flips) = data.split_once("\n\n").unwrap();
-
-    // Make list of (x, y) Points
-    let points = points.lines()
-        .map(|line| line.split(','))
-        .map(|s| s.into()).collect();
-
-    // Make list of ('x|y', n) flips
-    let flips = flips.lines()
-    .map(|line| line[11..].split("="))
-    .map(|mut split| (
-        split.next().unwrap().parse().unwrap(),
-        split.next().unwrap().parse().unwrap()
-    )).collect();
-
-    (points, flips)
-}
+pub mod ocr;
+pub mod paper;
 
 ///
 /// Convert a list of points into a list of strings
 /// for displaying the code.
 /// 
-pub fn make_strings(points:&Vec<Point>) -> Vec<String>{
-    let x_max = points.iter().map(|p| p.x).max().unwrap() + 1;
-    let y_max = points.iter().map(|p| p.y).max().unwrap() + 1;
+pub fn make_strings<'a>(points: impl IntoIterator<Item = &'a Point> + Clone) -> Vec<String>{
+    let x_max = points.clone().into_iter().map(|p| p.x).max().unwrap_or(0) + 1;
+    let y_max = points.clone().into_iter().map(|p| p.y).max().unwrap_or(0) + 1;
 
     let mut s = vec![' '; x_max * y_max];
     for p in points {
@@ -35,14 +15,14 @@ pub fn make_strings(points:&Vec<Point>) -> Vec<String>{
     }
     s.chunks(x_max)
     .take(y_max)
-    .map(|c| c.into_iter().collect())
+    .map(|c| c.iter().collect())
     .collect()
 }
 
 ///
 /// Point Struct and methods
 /// 
-#[derive(Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Point {
     pub x: usize,
     pub y: usize
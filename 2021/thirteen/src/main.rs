@@ -1,7 +1,7 @@
 use std::fs;
 use std::path::Path;
-use std::collections::HashSet;
-use thirteen::{parse_data, Point, make_strings};
+use thirteen::make_strings;
+use thirteen::paper::Paper;
 
 const PATH:&str = "./data.txt";
 
@@ -9,22 +9,18 @@ const PATH:&str = "./data.txt";
 fn main() {
     let path = Path::new(PATH);
     let raw_data = fs::read_to_string(path).expect("could not read file");
-    let (points, folds) = parse_data(&raw_data);
+    let paper:Paper = raw_data.parse().unwrap_or_else(|e| panic!("{}", e));
 
-    let points:Vec<Point> = points.into_iter()
-    .map(|point| point.transform_on_axis(&folds[0])).collect();
-    
-    let count:HashSet<&Point> = HashSet::from_iter(points.iter());
-    println!("Solution 1: {}", count.len());
+    let count = paper.fold_to(1).unwrap_or_else(|e| panic!("{}", e)).len();
+    println!("Solution 1: {}", count);
 
-    // For each point in the input
-    // transform it along the correct axis
-    let points:Vec<Point> = points.into_iter()
-        .map(|point| folds[1..].iter()
-            .fold(point, |p, fold| p.transform_on_axis(fold))).collect();
-
-    // Once all points are processed for all folds, figure out 
-    // where they are on the paper and print
-    let output = make_strings(&points).join("\n");
-    println!("Solution 2: \n{}", output);
+    // Once all points are processed for all folds, read the letters
+    // off the paper. If there's one we don't know print the dots instead.
+    match paper.read_code().unwrap_or_else(|e| panic!("{}", e)) {
+        Some(code) => println!("Solution 2: {}", code),
+        None => {
+            let dots = paper.folded().unwrap_or_else(|e| panic!("{}", e));
+            println!("Solution 2: \n{}", make_strings(&dots).join("\n"));
+        }
+    }
 }
@@ -0,0 +1,77 @@
+///
+/// Reads the 4x6 block letters Advent of Code draws its answers with.
+/// Letters are four columns wide with a blank column between them.
+///
+pub const HEIGHT: usize = 6;
+pub const WIDTH: usize = 4;
+
+// Also used by 2022/day_10 for its CRT.
+// Only letters that have turned up in puzzle answers; Y is five wide so can't be here.
+const FONT: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####")
+];
+
+/// The letter drawn in a 4x6 cell, if it's one we know.
+pub fn letter(cell: &[[bool; WIDTH]; HEIGHT]) -> Option<char> {
+    FONT.iter()
+        .find(|(_, glyph)| {
+            glyph.bytes()
+                .zip(cell.iter().flatten())
+                .all(|(g, lit)| (g == b'#') == *lit)
+        })
+        .map(|(c, _)| *c)
+}
+
+///
+/// Reads a row of letters from a function that says which points (x, y)
+/// are lit. Returns None if any letter isn't recognised.
+///
+pub fn read(width: usize, lit: impl Fn(usize, usize) -> bool) -> Option<String> {
+    (0..width.div_ceil(WIDTH + 1))
+        .map(|n| {
+            let mut cell = [[false; WIDTH]; HEIGHT];
+            for (y, row) in cell.iter_mut().enumerate() {
+                for (x, c) in row.iter_mut().enumerate() {
+                    *c = lit(n * (WIDTH + 1) + x, y);
+                }
+            }
+            letter(&cell)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let drawing = [
+            "#..#.####.###..",
+            "#..#.#....#..#.",
+            "####.###..###..",
+            "#..#.#....#..#.",
+            "#..#.#....#..#.",
+            "#..#.####.###..",
+        ];
+        let lit = |x: usize, y: usize| drawing[y].as_bytes().get(x) == Some(&b'#');
+        assert_eq!(read(15, lit), Some("HEB".to_string()));
+        assert_eq!(read(15, |_, _| true), None);
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+use crate::{ocr, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fold {
+    pub axis: Axis,
+    pub line: usize
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PaperError {
+    BadPoint(usize, String),
+    BadFold(usize, FoldError),
+    // the fold number (from 0) and the offending dot
+    DotOnFold(usize, Point),
+    FlapTooLong(usize, Point)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FoldError {
+    // doesn't start `fold along` or has no `=`
+    NotAFold(String),
+    BadAxis(String),
+    BadLine(String)
+}
+
+impl fmt::Display for FoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FoldError::NotAFold(s) => write!(f, "'{}' isn't 'fold along axis=line'", s),
+            FoldError::BadAxis(a) => write!(f, "can't fold along '{}', only x or y", a),
+            FoldError::BadLine(n) => write!(f, "'{}' isn't a line to fold on", n)
+        }
+    }
+}
+
+impl fmt::Display for PaperError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaperError::BadPoint(n, line) => write!(f, "line {}: can't read dot '{}'", n, line),
+            PaperError::BadFold(n, e) => write!(f, "line {}: {}", n, e),
+            PaperError::DotOnFold(i, p) => write!(f, "fold {} goes through the dot at {},{}", i + 1, p.x, p.y),
+            PaperError::FlapTooLong(i, p) => {
+                write!(f, "fold {} puts the dot at {},{} past the edge of the paper", i + 1, p.x, p.y)
+            }
+        }
+    }
+}
+
+impl Fold {
+    /// Where a point ends up after this fold.
+    /// Dots on the fold line or too far past it are errors.
+    pub fn apply(&self, p: Point, fold_number: usize) -> Result<Point, PaperError> {
+        let c = match self.axis {
+            Axis::X => p.x,
+            Axis::Y => p.y
+        };
+        if c == self.line {
+            return Err(PaperError::DotOnFold(fold_number, p))
+        }
+        if c > 2 * self.line {
+            return Err(PaperError::FlapTooLong(fold_number, p))
+        }
+        let axis = match self.axis {
+            Axis::X => 'x',
+            Axis::Y => 'y'
+        };
+        Ok(p.transform_on_axis(&(axis, self.line)))
+    }
+}
+
+impl FromStr for Fold {
+    type Err = FoldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (axis, line) = s.trim()
+            .strip_prefix("fold along ")
+            .and_then(|f| f.split_once('='))
+            .ok_or_else(|| FoldError::NotAFold(s.to_string()))?;
+        let axis = match axis {
+            "x" => Axis::X,
+            "y" => Axis::Y,
+            _ => return Err(FoldError::BadAxis(axis.to_string()))
+        };
+        Ok(Fold { axis, line: line.parse().map_err(|_| FoldError::BadLine(line.to_string()))? })
+    }
+}
+
+///
+/// The transparent paper: the dots as they were first marked
+/// and the folds to make in order.
+///
+#[derive(Debug)]
+pub struct Paper {
+    pub dots: Vec<Point>,
+    pub folds: Vec<Fold>
+}
+
+impl FromStr for Paper {
+    type Err = PaperError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut dots = vec![];
+        let mut folds = vec![];
+
+        for (i, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            if line.starts_with("fold") {
+                folds.push(line.parse().map_err(|e| PaperError::BadFold(i + 1, e))?);
+                continue
+            }
+            let point = line.trim()
+                .split_once(',')
+                .and_then(|(x, y)| Some(Point { x: x.parse().ok()?, y: y.parse().ok()? }))
+                .ok_or_else(|| PaperError::BadPoint(i + 1, line.to_string()))?;
+            dots.push(point);
+        }
+        Ok(Paper { dots, folds })
+    }
+}
+
+impl Paper {
+    fn follow(&self, p: Point, steps: usize) -> Result<Point, PaperError> {
+        self.folds.iter()
+            .take(steps)
+            .enumerate()
+            .try_fold(p, |p, (i, fold)| fold.apply(p, i))
+    }
+
+    /// The dots left after making the first `steps` folds.
+    pub fn fold_to(&self, steps: usize) -> Result<BTreeSet<Point>, PaperError> {
+        self.dots.iter().map(|p| self.follow(*p, steps)).collect()
+    }
+
+    /// The dots left after every fold.
+    pub fn folded(&self) -> Result<BTreeSet<Point>, PaperError> {
+        self.fold_to(self.folds.len())
+    }
+
+    /// The reverse of `fold_to`: each dot after `steps` folds
+    /// along with the original dots that ended up there.
+    pub fn unfold(&self, steps: usize) -> Result<BTreeMap<Point, Vec<Point>>, PaperError> {
+        let mut sources:BTreeMap<Point, Vec<Point>> = BTreeMap::new();
+        for p in &self.dots {
+            sources.entry(self.follow(*p, steps)?).or_default().push(*p);
+        }
+        for v in sources.values_mut() {
+            v.sort();
+        }
+        Ok(sources)
+    }
+
+    /// Folds everything and reads the code off the paper.
+    pub fn read_code(&self) -> Result<Option<String>, PaperError> {
+        let dots = self.folded()?;
+        let width = dots.iter().map(|p| p.x + 1).max().unwrap_or(0);
+        Ok(ocr::read(width, |x, y| dots.contains(&Point { x, y })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+fold along x=5";
+
+    #[test]
+    fn test_fold_to() {
+        let paper:Paper = EXAMPLE.parse().unwrap();
+        assert_eq!(paper.fold_to(0).unwrap().len(), 18);
+        assert_eq!(paper.fold_to(1).unwrap().len(), 17);
+        assert_eq!(paper.folded().unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_unfold() {
+        let paper:Paper = EXAMPLE.parse().unwrap();
+        let sources = paper.unfold(1).unwrap();
+        assert_eq!(sources[&Point { x: 0, y: 0 }], vec![Point { x: 0, y: 14 }]);
+        assert_eq!(sources[&Point { x: 8, y: 4 }], vec![Point { x: 8, y: 4 }, Point { x: 8, y: 10 }]);
+        assert_eq!(sources.values().map(|v| v.len()).sum::<usize>(), 18);
+    }
+
+    #[test]
+    fn test_bad_folds() {
+        let paper:Paper = "1,5\n\nfold along y=5".parse().unwrap();
+        assert_eq!(paper.folded(), Err(PaperError::DotOnFold(0, Point { x: 1, y: 5 })));
+
+        let paper:Paper = "1,12\n\nfold along y=5".parse().unwrap();
+        assert_eq!(paper.folded(), Err(PaperError::FlapTooLong(0, Point { x: 1, y: 12 })));
+
+        let err = "1,12\n\nfold along z=5".parse::<Paper>().unwrap_err();
+        assert_eq!(err, PaperError::BadFold(3, FoldError::BadAxis("z".to_string())));
+
+        assert_eq!("fold along x=7".parse(), Ok(Fold { axis: Axis::X, line: 7 }));
+        assert_eq!("fold along z=5".parse::<Fold>(), Err(FoldError::BadAxis("z".to_string())));
+        assert_eq!("fold along y=-1".parse::<Fold>(), Err(FoldError::BadLine("-1".to_string())));
+        assert_eq!("fold y=1".parse::<Fold>(), Err(FoldError::NotAFold("fold y=1".to_string())));
+        let err = "1;12".parse::<Paper>().unwrap_err();
+        assert_eq!(err, PaperError::BadPoint(1, "1;12".to_string()));
+    }
+
+    #[test]
+    fn test_read_code() {
+        // "HI" with a mirrored copy below y=6 that the fold lines back up
+        let drawing = [
+            "#..#..###",
+            "#..#...#.",
+            "####...#.",
+            "#..#...#.",
+            "#..#...#.",
+            "#..#..###",
+        ];
+        let mut input = String::new();
+        for (y, row) in drawing.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    input.push_str(&format!("{},{}\n{},{}\n", x, y, x, 12 - y));
+                }
+            }
+        }
+        input.push_str("\nfold along y=6\n");
+        let paper:Paper = input.parse().unwrap();
+        assert_eq!(paper.read_code(), Ok(Some("HI".to_string())));
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt;
+use std::str::FromStr;
+
+pub const MAX_WIDTH: usize = 128;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiagnosticError {
+    Empty,
+    BadLine(usize, String),
+    // line number, the width so far and the width of this line
+    Width(usize, usize, usize),
+    TooWide(usize)
+}
+
+impl fmt::Display for DiagnosticError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiagnosticError::Empty => write!(f, "the report is empty"),
+            DiagnosticError::BadLine(n, line) => write!(f, "line {}: '{}' isn't binary", n, line),
+            DiagnosticError::Width(n, expected, found) => {
+                write!(f, "line {}: expected {} bits but found {}", n, expected, found)
+            },
+            DiagnosticError::TooWide(w) => write!(f, "{} bits is more than {} we can handle", w, MAX_WIDTH)
+        }
+    }
+}
+
+/// Which bits to keep when filtering for a rating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criteria {
+    MostCommon,
+    LeastCommon
+}
+
+/// The bit to keep when there are as many ones as zeros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tie {
+    Zero,
+    One
+}
+
+// One node per prefix, with how many numbers start with it
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: [Option<usize>; 2],
+    count: usize
+}
+
+///
+/// The diagnostic numbers, all the same width, in a binary trie
+/// so a rating only has to walk down it once.
+///
+#[derive(Debug)]
+pub struct Report {
+    pub width: usize,
+    pub values: Vec<u128>,
+    // node 0 is the root
+    trie: Vec<Node>
+}
+
+impl Report {
+    pub fn new(values: Vec<u128>, width: usize) -> Result<Self, DiagnosticError> {
+        if width > MAX_WIDTH {
+            return Err(DiagnosticError::TooWide(width))
+        }
+        let mut trie = vec![Node::default()];
+        for &v in &values {
+            let mut node = 0;
+            trie[0].count += 1;
+            for bit in (0..width).rev() {
+                let b = (v >> bit & 1) as usize;
+                node = match trie[node].children[b] {
+                    Some(child) => child,
+                    None => {
+                        trie.push(Node::default());
+                        trie[node].children[b] = Some(trie.len() - 1);
+                        trie.len() - 1
+                    }
+                };
+                trie[node].count += 1;
+            }
+        }
+        Ok(Report { width, values, trie })
+    }
+
+    fn mask(&self) -> u128 {
+        if self.width == MAX_WIDTH { u128::MAX } else { (1 << self.width) - 1 }
+    }
+
+    /// The most common bit in each position, with ties going to `tie`.
+    pub fn gamma(&self, tie: Tie) -> u128 {
+        (0..self.width).fold(0, |gamma, bit| {
+            let ones = self.values.iter().filter(|&&v| v >> bit & 1 == 1).count();
+            let zeros = self.values.len() - ones;
+            let one = ones > zeros || (ones == zeros && tie == Tie::One);
+            if one { gamma | 1 << bit } else { gamma }
+        })
+    }
+
+    /// The least common bits: every bit of gamma flipped.
+    pub fn epsilon(&self, tie: Tie) -> u128 {
+        !self.gamma(tie) & self.mask()
+    }
+
+    /// Gamma times epsilon, if it fits.
+    pub fn power_consumption(&self) -> Option<u128> {
+        self.gamma(Tie::One).checked_mul(self.epsilon(Tie::One))
+    }
+
+    ///
+    /// Follows the trie from the top bit down, taking the branch the criteria
+    /// picks at each level. A branch with nothing in it is never taken,
+    /// so this always ends on a number. None if the report is empty.
+    ///
+    pub fn rating(&self, criteria: Criteria, tie: Tie) -> Option<u128> {
+        if self.values.is_empty() {
+            return None
+        }
+        let mut node = 0;
+        let mut value = 0;
+        for _ in 0..self.width {
+            let count = |b: usize| self.trie[node].children[b].map_or(0, |c| self.trie[c].count);
+            let (zeros, ones) = (count(0), count(1));
+            let b = if zeros == 0 || ones == 0 {
+                if ones > 0 { 1 } else { 0 }
+            } else if zeros == ones {
+                tie as usize
+            } else {
+                match criteria {
+                    Criteria::MostCommon => (ones > zeros) as usize,
+                    Criteria::LeastCommon => (ones < zeros) as usize
+                }
+            };
+            value = value << 1 | b as u128;
+            node = self.trie[node].children[b]?;
+        }
+        Some(value)
+    }
+
+    pub fn oxygen(&self) -> Option<u128> {
+        self.rating(Criteria::MostCommon, Tie::One)
+    }
+
+    pub fn co2(&self) -> Option<u128> {
+        self.rating(Criteria::LeastCommon, Tie::Zero)
+    }
+
+    /// Oxygen times CO₂, if it fits.
+    pub fn life_support(&self) -> Option<u128> {
+        self.oxygen()?.checked_mul(self.co2()?)
+    }
+}
+
+impl FromStr for Report {
+    type Err = DiagnosticError;
+
+    /// One binary number per line. The width is taken from the first line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut values = vec![];
+        for (i, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let line = line.trim();
+            let w = *width.get_or_insert(line.len());
+            if w > MAX_WIDTH {
+                return Err(DiagnosticError::TooWide(w))
+            }
+            if line.len() != w {
+                return Err(DiagnosticError::Width(i + 1, w, line.len()))
+            }
+            let v = u128::from_str_radix(line, 2)
+                .ok()
+                .filter(|_| !line.starts_with('+'))
+                .ok_or_else(|| DiagnosticError::BadLine(i + 1, line.to_string()))?;
+            values.push(v);
+        }
+        Report::new(values, width.ok_or(DiagnosticError::Empty)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
+
+    #[test]
+    fn test_example() {
+        let report:Report = EXAMPLE.parse().unwrap();
+        assert_eq!(report.width, 5);
+        assert_eq!((report.gamma(Tie::One), report.epsilon(Tie::One)), (22, 9));
+        assert_eq!(report.power_consumption(), Some(198));
+        assert_eq!((report.oxygen(), report.co2()), (Some(23), Some(10)));
+        assert_eq!(report.life_support(), Some(230));
+    }
+
+    #[test]
+    fn test_ties() {
+        // three numbers: two ones is the most common, not a tie
+        let report:Report = "1\n1\n0".parse().unwrap();
+        assert_eq!((report.gamma(Tie::Zero), report.epsilon(Tie::Zero)), (1, 0));
+
+        let report:Report = "10\n01".parse().unwrap();
+        assert_eq!(report.oxygen(), Some(0b10));
+        assert_eq!(report.co2(), Some(0b01));
+        assert_eq!(report.rating(Criteria::MostCommon, Tie::Zero), Some(0b01));
+        assert_eq!(report.gamma(Tie::Zero), 0);
+        assert_eq!(report.gamma(Tie::One), 0b11);
+    }
+
+    #[test]
+    fn test_wide() {
+        let ones = "1".repeat(128);
+        let report:Report = format!("{}\n{}\n0{}", ones, ones, &ones[1..]).parse().unwrap();
+        assert_eq!(report.width, 128);
+        assert_eq!(report.gamma(Tie::One), u128::MAX);
+        assert_eq!(report.epsilon(Tie::One), 0);
+        assert_eq!(report.co2(), Some(u128::MAX >> 1));
+
+        assert_eq!(format!("{}1", ones).parse::<Report>().unwrap_err(), DiagnosticError::TooWide(129));
+    }
+
+    #[test]
+    fn test_bad_input() {
+        assert_eq!("101\n11".parse::<Report>().unwrap_err(), DiagnosticError::Width(2, 3, 2));
+        assert_eq!("101\n121".parse::<Report>().unwrap_err(), DiagnosticError::BadLine(2, "121".to_string()));
+        assert_eq!("".parse::<Report>().unwrap_err(), DiagnosticError::Empty);
+    }
+}
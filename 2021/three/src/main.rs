@@ -1,71 +1,24 @@
-
-use std::io::{BufReader, Read, BufRead};
+use std::fs;
 use std::path::Path;
-use std::fs::File;
-
-static PATH: &'static str = "./data.txt";
-
-fn read_integers<R: Read>(input: R) -> Vec<usize> {
-    let in_data = BufReader::new(input);
-    in_data.lines()
-        .map(|line| usize::from_str_radix(&line.unwrap(), 2).expect("The elves are having trouble with binary math!"))
-        .collect()
-}
-
-fn solution1(data:&Vec<usize>, bits:usize) -> usize {
-    let data_size = data.len();
-    let most = (0..bits)
-        .map(|n| 1 << n)
-        .fold(0, |res, p| {
-            if data.iter().filter(|n| *n & p == 0 ).count() >= data_size / 2 {res + p} else { res }
-        });
-    let least = (0..bits)
-        .map(|n| 1 << n)
-        .fold(0, |res, p| {
-            if data.iter().filter(|n| *n & p == 0 ).count() < data_size / 2 {res + p} else { res }
-        });
+use three::diagnostics::Report;
 
-    most * least
-}
-
-fn solution2(data:&Vec<usize>, bits:usize) -> usize {
-    let mut filtered = data.clone();
-    let mut p = bits ;
-    while filtered.len() > 1 {
-        p -= 1;
-        let (zeros, ones):(Vec<usize>, Vec<usize>) = filtered.into_iter().partition(|&n| n & (1 << p) == 0);
-        filtered = if zeros.len() > ones.len() {zeros} else {ones};
-    }
-    let oxygen = filtered[0];
-    
-    let mut filtered = data.clone();
-    let mut p =  bits ;
-    while filtered.len() > 1 {
-        p -= 1;
-        let (zeros, ones):(Vec<usize>, Vec<usize>) = filtered.iter().partition(|&n| n & (1 << p) == 0);
-        filtered = if zeros.len() > ones.len() {ones} else {zeros};
-    }
-    let co2 = filtered[0];
-    
-    co2 * oxygen
-}
+static PATH: &str = "./data.txt";
 
 fn main() {
     let path = Path::new(PATH);
-    let file = File::open(&path).expect("Error opening file, submarine problems!");
-    let data:Vec<usize> = read_integers(file);
+    let data = fs::read_to_string(path).expect("Error opening file, submarine problems!");
+    let report:Report = data.parse().unwrap_or_else(|e| panic!("{}", e));
 
-    println!("{:?}", solution1(&data, 12));
-    println!("{:?}", solution2(&data, 12));
+    println!("{:?}", report.power_consumption().expect("Power consumption is off the scale"));
+    println!("{:?}", report.life_support().expect("Life support is off the scale"));
 }
 
-
 #[cfg(test)]
 mod test {
     use super::*;
     #[test]
     fn solution_one(){
-        let data:Vec<usize> = vec![
+        let data:Vec<u128> = vec![
             0b00100,
             0b11110,
             0b10110,
@@ -79,11 +32,12 @@ mod test {
             0b00010,
             0b01010
         ];
-        assert_eq!(solution1(&data, 5), 198);
+        let report = Report::new(data, 5).unwrap();
+        assert_eq!(report.power_consumption(), Some(198));
     }
     #[test]
     fn solution_two(){
-        let data:Vec<usize> = vec![
+        let data:Vec<u128> = vec![
             0b00100,
             0b11110,
             0b10110,
@@ -97,6 +51,7 @@ mod test {
             0b00010,
             0b01010
         ];
-        assert_eq!(solution2(&data, 5), 230);
+        let report = Report::new(data, 5).unwrap();
+        assert_eq!(report.life_support(), Some(230));
     }
 }
\ No newline at end of file
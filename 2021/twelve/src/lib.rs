@@ -1,93 +1,322 @@
-use std::collections::{HashMap, HashSet, BTreeSet};
-
-type Graph<'a> = HashMap<&'a str, HashSet<&'a str>>;
-
-// fn is_upper(s: &str) -> bool {
-//     !s.chars().any(|c| c.is_lowercase())
-// }
+use std::collections::HashMap;
+use std::fmt;
 
 fn is_lower(s: &str) -> bool {
     !s.chars().any(|c| c.is_uppercase())
 }
 
-pub fn parse_input(s: &str) -> Graph {
-    let mut g = HashMap::new();
-    for line in s.lines() {
-        let mut pair = line.split('-');
-        let source = pair.next().unwrap();
-        let dest = pair.next().unwrap();
-        if dest != "start" && source != "end" {
-            g.entry(source).or_insert(HashSet::new()).insert(dest);
+#[derive(Debug, PartialEq, Eq)]
+pub enum CaveError {
+    BadLine(usize, String),
+    UnknownCave(String),
+    TooManyCaves(usize),
+    // two big caves joined together, so a path could bounce between them forever
+    Endless(String, String),
+    // a cave joined to itself
+    SelfLoop(usize, String),
+    NoVisits
+}
+
+impl fmt::Display for CaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CaveError::BadLine(n, line) => write!(f, "line {}: expected 'a-b' but found '{}'", n, line),
+            CaveError::UnknownCave(name) => write!(f, "no cave called '{}' is connected to anything", name),
+            CaveError::TooManyCaves(n) => write!(f, "{} small caves is too many to track", n),
+            CaveError::Endless(a, b) => write!(f, "{} and {} are both big, so there are endless paths", a, b),
+            CaveError::SelfLoop(n, name) => write!(f, "line {}: {} is joined to itself", n, name),
+            CaveError::NoVisits => write!(f, "caves have to be visitable at least once")
+        }
+    }
+}
+
+/// The cave system with every cave given an index. All edges are kept,
+/// including those into `start` and out of `end`, the revisit policy
+/// decides which ones can be used.
+#[derive(Debug, Default)]
+pub struct CaveGraph {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    edges: Vec<Vec<usize>>
+}
+
+impl CaveGraph {
+    fn add_cave(&mut self, name: &str) -> usize {
+        if let Some(&i) = self.index.get(name) {
+            return i
         }
+        self.names.push(name.to_string());
+        self.edges.push(vec![]);
+        self.index.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    pub fn cave(&self, name: &str) -> Result<usize, CaveError> {
+        self.index.get(name).copied().ok_or_else(|| CaveError::UnknownCave(name.to_string()))
+    }
+
+    pub fn name(&self, cave: usize) -> &str {
+        &self.names[cave]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn neighbors(&self, name: &str) -> Result<Vec<&str>, CaveError> {
+        Ok(self.edges[self.cave(name)?].iter().map(|&i| self.name(i)).collect())
+    }
+}
+
+pub fn parse_input(s: &str) -> Result<CaveGraph, CaveError> {
+    let mut g = CaveGraph::default();
+    for (n, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let (source, dest) = line.trim()
+            .split_once('-')
+            .filter(|(a, b)| !a.is_empty() && !b.is_empty())
+            .ok_or_else(|| CaveError::BadLine(n + 1, line.to_string()))?;
+        if source == dest {
+            return Err(CaveError::SelfLoop(n + 1, source.to_string()))
+        }
+        let (a, b) = (g.add_cave(source), g.add_cave(dest));
+        if !g.edges[a].contains(&b) {
+            g.edges[a].push(b);
+            g.edges[b].push(a);
+        }
+    }
+    // keep the paths in a predictable order
+    for i in 0..g.len() {
+        let mut e = std::mem::take(&mut g.edges[i]);
+        e.sort_by(|a, b| g.names[*a].cmp(&g.names[*b]));
+        g.edges[i] = e;
+    }
+    Ok(g)
+}
+
+/// How often caves may be entered. Small caves may normally be visited once;
+/// up to `extra_caves` of the revisitable ones may be visited up to
+/// `max_visits` times. Big caves are unlimited.
+pub struct RevisitPolicy {
+    max_visits: u8,
+    extra_caves: usize,
+    small: Box<dyn Fn(&str) -> bool>,
+    revisitable: Box<dyn Fn(&str) -> bool>
+}
 
-        if dest != "end" && source != "start" {
-            g.entry(dest).or_insert(HashSet::new()).insert(source);
+impl RevisitPolicy {
+    pub fn new(max_visits: u8, extra_caves: usize) -> Result<RevisitPolicy, CaveError> {
+        if max_visits == 0 {
+            return Err(CaveError::NoVisits)
         }
+        Ok(RevisitPolicy {
+            max_visits,
+            extra_caves,
+            small: Box::new(is_lower),
+            revisitable: Box::new(|name| name != "start" && name != "end")
+        })
+    }
+
+    /// Part one: every small cave at most once.
+    pub fn once() -> RevisitPolicy {
+        RevisitPolicy::new(1, 0).unwrap()
+    }
+
+    /// Part two: a single small cave may be visited twice.
+    pub fn one_twice() -> RevisitPolicy {
+        RevisitPolicy::new(2, 1).unwrap()
     }
-    g
+
+    pub fn max_visits(&self) -> u8 {
+        self.max_visits
+    }
+
+    pub fn extra_caves(&self) -> usize {
+        self.extra_caves
+    }
+
+    pub fn with_small(mut self, small: impl Fn(&str) -> bool + 'static) -> RevisitPolicy {
+        self.small = Box::new(small);
+        self
+    }
+
+    pub fn with_revisitable(mut self, revisitable: impl Fn(&str) -> bool + 'static) -> RevisitPolicy {
+        self.revisitable = Box::new(revisitable);
+        self
+    }
+}
+
+// Visit counts for the small caves are packed into a u128,
+// a few bits per cave, so they can be used as part of a memo key.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Visits {
+    counts: u128,
+    extras: usize
 }
 
-pub fn count_paths_one(graph: &Graph, start: &str, seen: Option<HashSet<&str>>) -> usize {
-    let seen = seen.unwrap_or_default();
-    if start == "end" {
-        return 1
+pub struct PathEngine<'g> {
+    graph: &'g CaveGraph,
+    start: usize,
+    end: usize,
+    max_visits: u8,
+    extra_caves: usize,
+    // position of each small cave in the packed counts
+    slot: Vec<Option<u32>>,
+    revisitable: Vec<bool>,
+    bits: u32
+}
+
+impl<'g> PathEngine<'g> {
+    pub fn new(graph: &'g CaveGraph, start: &str, end: &str, policy: &RevisitPolicy) -> Result<PathEngine<'g>, CaveError> {
+        let bits = u8::BITS - policy.max_visits.leading_zeros();
+        let mut small_count = 0;
+        let slot = graph.names.iter().map(|name| {
+            if (policy.small)(name) {
+                small_count += 1;
+                Some((small_count - 1) * bits)
+            } else {
+                None
+            }
+        }).collect();
+
+        if small_count * bits > u128::BITS {
+            return Err(CaveError::TooManyCaves(small_count as usize))
+        }
+
+        // Any loop made only of big caves has a big to big edge in it. Paths
+        // stop at the end so edges into it can't loop.
+        let (start, end) = (graph.cave(start)?, graph.cave(end)?);
+        let big = |cave: usize| !(policy.small)(graph.name(cave)) && cave != end;
+        for (a, edges) in graph.edges.iter().enumerate() {
+            if let Some(&b) = edges.iter().find(|&&b| big(a) && big(b)) {
+                return Err(CaveError::Endless(graph.name(a).to_string(), graph.name(b).to_string()))
+            }
+        }
+
+        Ok(PathEngine {
+            graph,
+            start,
+            end,
+            max_visits: policy.max_visits,
+            extra_caves: policy.extra_caves,
+            slot,
+            revisitable: graph.names.iter().map(|name| (policy.revisitable)(name)).collect(),
+            bits
+        })
+    }
+
+    fn count_of(&self, visits: Visits, cave: usize) -> Option<u8> {
+        let shift = self.slot[cave]?;
+        Some(((visits.counts >> shift) & ((1 << self.bits) - 1)) as u8)
+    }
+
+    // Returns the new visit state if the cave can be entered
+    fn enter(&self, visits: Visits, cave: usize) -> Option<Visits> {
+        let Some(count) = self.count_of(visits, cave) else {
+            return Some(visits)
+        };
+        let mut extras = visits.extras;
+        if count >= 1 {
+            if count >= self.max_visits || !self.revisitable[cave] {
+                return None
+            }
+            // the second visit is the one that uses up an extra
+            if count == 1 {
+                if extras >= self.extra_caves {
+                    return None
+                }
+                extras += 1;
+            }
+        }
+        Some(Visits { counts: visits.counts + (1 << self.slot[cave]?), extras })
+    }
+
+    fn start_visits(&self) -> Visits {
+        let empty = Visits { counts: 0, extras: 0 };
+        self.enter(empty, self.start).unwrap_or(empty)
+    }
+
+    /// The number of distinct paths from start to end.
+    pub fn count(&self) -> u64 {
+        let mut memo = HashMap::new();
+        self.count_from(self.start, self.start_visits(), &mut memo)
     }
-    let mut total = 0;
-    for &val in graph.get(start).unwrap() {
-        if seen.contains(val){
-            continue
+
+    fn count_from(&self, cave: usize, visits: Visits, memo: &mut HashMap<(usize, Visits), u64>) -> u64 {
+        if cave == self.end {
+            return 1
         }
-        let mut local_seen:HashSet<&str> = HashSet::new();
-        if is_lower(val) {
-            local_seen.insert(val);
+        if let Some(&n) = memo.get(&(cave, visits)) {
+            return n
         }
-        local_seen.extend(&seen);
-        total += count_paths_one(graph, val, Some(local_seen));
-    }
-    total
-} 
-
-pub fn count_paths_two(
-    graph: &Graph, 
-    start: &str, 
-    seen: Option<BTreeSet<&str>>, 
-    twice:bool,
-) -> usize {
-  
-    let seen = seen.unwrap_or_default();
-
-    if start == "end" {
-        return 1
-    }
-    let mut total = 0;
-    for &val in graph.get(start).unwrap() {
-        let mut local_twice = twice;
-        let mut local_seen:BTreeSet<&str> = BTreeSet::new();
-        if is_lower(val) {
-            local_seen.insert(val);
-            if seen.contains(val) {
-                if local_twice {
-                    continue
+        let total = self.graph.edges[cave]
+            .iter()
+            .filter_map(|&next| Some((next, self.enter(visits, next)?)))
+            .map(|(next, v)| self.count_from(next, v, memo))
+            .sum();
+        memo.insert((cave, visits), total);
+        total
+    }
+
+    /// Every path from start to end, as cave names.
+    pub fn paths(&self) -> Paths<'_, 'g> {
+        Paths {
+            engine: self,
+            stack: vec![(self.start, 0, self.start_visits())]
+        }
+    }
+}
+
+/// Depth first walk over the paths; each stack frame holds a cave,
+/// the next edge to try from it and the visits on arriving there.
+pub struct Paths<'e, 'g> {
+    engine: &'e PathEngine<'g>,
+    stack: Vec<(usize, usize, Visits)>
+}
+
+impl<'e, 'g> Iterator for Paths<'e, 'g> {
+    type Item = Vec<&'g str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.engine.graph;
+        while let Some(frame) = self.stack.last_mut() {
+            let (cave, edge, visits) = *frame;
+            let Some(&next) = graph.edges[cave].get(edge) else {
+                self.stack.pop();
+                continue
+            };
+            frame.1 += 1;
+
+            if let Some(v) = self.engine.enter(visits, next) {
+                if next == self.engine.end {
+                    let mut path:Vec<&str> = self.stack.iter().map(|f| graph.name(f.0)).collect();
+                    path.push(graph.name(next));
+                    return Some(path)
                 }
-                local_twice = true;
+                self.stack.push((next, 0, v));
             }
         }
-        local_seen.extend(&seen);
-        let subsum = count_paths_two(graph, val, Some(local_seen), local_twice);
-        total += subsum;
+        None
     }
-    
-    total
-} 
+}
+
+pub fn count_paths_one(graph: &CaveGraph) -> Result<u64, CaveError> {
+    Ok(PathEngine::new(graph, "start", "end", &RevisitPolicy::once())?.count())
+}
+
+pub fn count_paths_two(graph: &CaveGraph) -> Result<u64, CaveError> {
+    Ok(PathEngine::new(graph, "start", "end", &RevisitPolicy::one_twice())?.count())
+}
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_graph_parse(){
-        let inp = "start-A\n\
+    static SMALL: &str = "start-A\n\
         start-b\n\
         A-c\n\
         A-b\n\
@@ -95,23 +324,18 @@ mod tests {
         A-end\n\
         b-end";
 
-        let g = parse_input(inp);
-        assert_eq!(g.get("start"), Some(&HashSet::from(["A", "b"])));
-        assert_eq!(g.get("A"), Some(&HashSet::from(["c", "b", "end"])));
-        assert_eq!(g.get("d"), Some(&HashSet::from(["b"])));
+    #[test]
+    fn test_graph_parse(){
+        let g = parse_input(SMALL).unwrap();
+        assert_eq!(g.neighbors("start"), Ok(vec!["A", "b"]));
+        assert_eq!(g.neighbors("A"), Ok(vec!["b", "c", "end", "start"]));
+        assert_eq!(g.neighbors("d"), Ok(vec!["b"]));
+        assert_eq!(parse_input("start-A\nA-").unwrap_err(), CaveError::BadLine(2, "A-".to_string()));
     }
     #[test]
     fn test_path_count_one(){
-        let inp = "start-A\n\
-        start-b\n\
-        A-c\n\
-        A-b\n\
-        b-d\n\
-        A-end\n\
-        b-end";
-
-        let g = parse_input(inp);
-        assert_eq!(count_paths_one(&g, "start", None), 10);
+        let g = parse_input(SMALL).unwrap();
+        assert_eq!(count_paths_one(&g), Ok(10));
     }
 
     #[test]
@@ -127,7 +351,60 @@ mod tests {
         kj-HN\n\
         kj-dc";
 
-        let g = parse_input(inp);
-        assert_eq!(count_paths_two(&g, "start", None, false), 103);
+        let g = parse_input(inp).unwrap();
+        assert_eq!(count_paths_two(&g), Ok(103));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_paths_match_count() {
+        let g = parse_input(SMALL).unwrap();
+        for policy in [RevisitPolicy::once(), RevisitPolicy::one_twice(), RevisitPolicy::new(3, 2).unwrap()] {
+            let engine = PathEngine::new(&g, "start", "end", &policy).unwrap();
+            let paths:Vec<Vec<&str>> = engine.paths().collect();
+            assert_eq!(paths.len() as u64, engine.count());
+        }
+        let engine = PathEngine::new(&g, "start", "end", &RevisitPolicy::once()).unwrap();
+        assert_eq!(engine.paths().next(), Some(vec!["start", "A", "b", "A", "c", "A", "end"]));
+        assert_eq!(RevisitPolicy::one_twice().max_visits(), 2);
+        assert_eq!(RevisitPolicy::new(0, 1).err(), Some(CaveError::NoVisits));
+    }
+
+    #[test]
+    fn test_custom_predicates() {
+        let g = parse_input(SMALL).unwrap();
+        // treating every cave as small means A can't be passed through twice
+        let policy = RevisitPolicy::once().with_small(|_| true);
+        let engine = PathEngine::new(&g, "start", "end", &policy).unwrap();
+        assert_eq!(engine.count(), 4);
+
+        // only c may be visited twice
+        let policy = RevisitPolicy::one_twice().with_revisitable(|name| name == "c");
+        let engine = PathEngine::new(&g, "start", "end", &policy).unwrap();
+        assert_eq!(engine.count(), 16);
+
+        let err = PathEngine::new(&g, "start", "end", &RevisitPolicy::once().with_small(|_| false)).err();
+        assert_eq!(err, Some(CaveError::Endless("start".to_string(), "A".to_string())));
+    }
+
+    #[test]
+    fn test_endless() {
+        let g = parse_input("start-A
+A-B
+B-end").unwrap();
+        assert_eq!(count_paths_one(&g), Err(CaveError::Endless("A".to_string(), "B".to_string())));
+        // big caves next to the end are fine, the path stops there
+        let g = parse_input("start-A
+A-END").unwrap();
+        let engine = PathEngine::new(&g, "start", "END", &RevisitPolicy::once()).unwrap();
+        assert_eq!(engine.count(), 1);
+    }
+
+    #[test]
+    fn test_unknown_cave() {
+        let g = parse_input(SMALL).unwrap();
+        let err = PathEngine::new(&g, "start", "exit", &RevisitPolicy::once()).err();
+        assert_eq!(err, Some(CaveError::UnknownCave("exit".to_string())));
+        assert_eq!(count_paths_one(&parse_input("a-b").unwrap()).unwrap_err(), CaveError::UnknownCave("start".to_string()));
+        assert_eq!(parse_input("start-a\na-a\na-end").unwrap_err(), CaveError::SelfLoop(2, "a".to_string()));
+    }
+}
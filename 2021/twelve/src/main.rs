@@ -7,11 +7,11 @@ const PATH: &str = "./data.txt";
 fn main() {
     let path = Path::new(PATH);
     let data = fs::read_to_string(path).expect("file not found...maybe it entered a small cave twice");
-    let graph = parse_input(&data);
+    let graph = parse_input(&data).unwrap_or_else(|e| panic!("{}", e));
 
-    let solution_one = count_paths_one(&graph, "start", None);
+    let solution_one = count_paths_one(&graph).unwrap_or_else(|e| panic!("{}", e));
     println!("solution one: {}", solution_one);
 
-    let solution_two = count_paths_two(&graph, "start", None, false);
+    let solution_two = count_paths_two(&graph).unwrap_or_else(|e| panic!("{}", e));
     println!("solution two: {}", solution_two);
 }
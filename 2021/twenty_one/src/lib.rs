@@ -0,0 +1,308 @@
+use std::fmt;
+
+type Player = (u32, u32);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    // line number and text of a line that isn't a starting position
+    BadLine(usize, String),
+    // line number and a position that isn't on the board
+    OffBoard(usize, u32),
+    MissingPlayer
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadLine(n, line) => write!(f, "line {}: can't read starting position from '{}'", n, line),
+            ParseError::OffBoard(n, pos) => write!(f, "line {}: there's no space {} on the board", n, pos),
+            ParseError::MissingPlayer => write!(f, "need a starting position for both players")
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RulesError {
+    ZeroBoard,
+    ZeroFaces,
+    ZeroRolls,
+    ZeroTarget,
+    // a starting position that isn't on the board
+    OffBoard(u32),
+    // faces and rolls that could total more than MAX_TURN in a turn
+    TurnTooBig(u32, u32),
+    // more universes than a u128 can count
+    Overflow
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::ZeroBoard => write!(f, "the board needs at least one space"),
+            RulesError::ZeroFaces => write!(f, "the die needs at least one face"),
+            RulesError::ZeroRolls => write!(f, "a turn needs at least one roll"),
+            RulesError::ZeroTarget => write!(f, "the target score has to be above zero"),
+            RulesError::OffBoard(pos) => write!(f, "there's no space {} on the board", pos),
+            RulesError::TurnTooBig(faces, rolls) => {
+                write!(f, "{} rolls of a {} sided die could total more than {}", rolls, faces, MAX_TURN)
+            },
+            RulesError::Overflow => write!(f, "too many universes to count")
+        }
+    }
+}
+
+/// Reads the two starting positions from lines like
+/// `Player 1 starting position: 4`, each one a space on a `board` sized board.
+pub fn parse_positions(s: &str, board: u32) -> Result<(u32, u32), ParseError> {
+    let mut positions = s.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, line)| {
+            let pos = line.rsplit_once(':')
+                .and_then(|(_, n)| n.trim().parse().ok())
+                .ok_or_else(|| ParseError::BadLine(i + 1, line.to_string()))?;
+            if !(1..=board).contains(&pos) {
+                return Err(ParseError::OffBoard(i + 1, pos))
+            }
+            Ok(pos)
+        });
+    let p1 = positions.next().ok_or(ParseError::MissingPlayer)??;
+    let p2 = positions.next().ok_or(ParseError::MissingPlayer)??;
+    Ok((p1, p2))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameRules {
+    // spaces on the circular board, numbered from 1
+    board: u32,
+    faces: u32,
+    rolls: u32,
+    target: u32
+}
+
+/// The most a single turn's rolls may add up to. The roll distribution
+/// has an entry for every total, so this keeps it a sensible size.
+pub const MAX_TURN: u32 = 1 << 16;
+
+impl GameRules {
+    pub fn new(board: u32, faces: u32, rolls: u32, target: u32) -> Result<Self, RulesError> {
+        match (board, faces, rolls, target) {
+            (0, _, _, _) => Err(RulesError::ZeroBoard),
+            (_, 0, _, _) => Err(RulesError::ZeroFaces),
+            (_, _, 0, _) => Err(RulesError::ZeroRolls),
+            (_, _, _, 0) => Err(RulesError::ZeroTarget),
+            _ if faces.checked_mul(rolls).is_none_or(|t| t > MAX_TURN) => Err(RulesError::TurnTooBig(faces, rolls)),
+            _ => Ok(GameRules { board, faces, rolls, target })
+        }
+    }
+
+    /// Part one: a 100 sided deterministic die, first to 1000.
+    pub fn deterministic() -> GameRules {
+        GameRules { board: 10, faces: 100, rolls: 3, target: 1000 }
+    }
+
+    /// Part two: the three sided Dirac die, first to 21.
+    pub fn dirac() -> GameRules {
+        GameRules { board: 10, faces: 3, rolls: 3, target: 21 }
+    }
+
+    pub fn board(&self) -> u32 {
+        self.board
+    }
+
+    pub fn faces(&self) -> u32 {
+        self.faces
+    }
+
+    pub fn rolls(&self) -> u32 {
+        self.rolls
+    }
+
+    pub fn target(&self) -> u32 {
+        self.target
+    }
+
+    fn check_start(&self, p1: u32, p2: u32) -> Result<(), RulesError> {
+        match [p1, p2].into_iter().find(|p| !(1..=self.board).contains(p)) {
+            Some(p) => Err(RulesError::OffBoard(p)),
+            None => Ok(())
+        }
+    }
+
+    /// How many ways each total can come up in one turn, indexed by total.
+    /// For the Dirac die this is the old THREE_ROLL_PERMUTATIONS table.
+    /// Many rolls of a small die give an Overflow error.
+    pub fn roll_distribution(&self) -> Result<Vec<u64>, RulesError> {
+        let mut ways:Vec<u64> = vec![1];
+        for _ in 0..self.rolls {
+            let mut next = vec![0u64; ways.len() + self.faces as usize];
+            for (total, &n) in ways.iter().enumerate() {
+                for face in 1..=self.faces as usize {
+                    next[total + face] = next[total + face].checked_add(n).ok_or(RulesError::Overflow)?;
+                }
+            }
+            ways = next;
+        }
+        Ok(ways)
+    }
+
+    pub fn update(&self, player: Player, roll: u32) -> Player {
+        let (pos, score) = player;
+        let pos = (pos - 1 + roll) % self.board + 1;
+        (pos, score + pos)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DeterministicResult {
+    pub winner: usize,
+    pub losing_score: u32,
+    pub rolls: u32
+}
+
+/// Plays with a die that counts up 1, 2, 3... wrapping back to 1.
+pub fn play_deterministic(rules: &GameRules, p1: u32, p2: u32) -> Result<DeterministicResult, RulesError> {
+    rules.check_start(p1, p2)?;
+    let mut players = [(p1, 0), (p2, 0)];
+    let mut die = (1..=rules.faces).cycle();
+    let mut rolls = 0;
+    let mut turn = 0;
+
+    loop {
+        let roll:u32 = die.by_ref().take(rules.rolls as usize).sum();
+        rolls += rules.rolls;
+        players[turn] = rules.update(players[turn], roll);
+        if players[turn].1 >= rules.target {
+            return Ok(DeterministicResult { winner: turn + 1, losing_score: players[1 - turn].1, rolls })
+        }
+        turn = 1 - turn;
+    }
+}
+
+/// Counts the universes each player wins in with the quantum die.
+/// The counts are u128, enough for the puzzle's rules and a good
+/// deal more. Targets in the fifties need more and give an Overflow error.
+///
+/// Rather than recursing from the start the table is filled bottom up.
+/// A state is (mover position, mover score, other position, other score)
+/// and a move always raises the total score, so states are filled
+/// from the highest total down and every state a move leads to is
+/// already known.
+pub fn play_dirac(rules: &GameRules, p1: u32, p2: u32) -> Result<(u128, u128), RulesError> {
+    rules.check_start(p1, p2)?;
+    let b = rules.board as usize;
+    let t = rules.target as usize;
+    let distribution:Vec<(u32, u128)> = rules.roll_distribution()?
+        .into_iter()
+        .enumerate()
+        .filter(|(_, n)| *n > 0)
+        .map(|(roll, n)| (roll as u32, n as u128))
+        .collect();
+
+    let add = |count: u128, n: u128, w: u128| n.checked_mul(w).and_then(|v| count.checked_add(v)).ok_or(RulesError::Overflow);
+    let index = |pa: usize, sa: usize, pb: usize, sb: usize| ((pa * t + sa) * b + pb) * t + sb;
+    // wins for the player about to move and for the other player
+    let mut table = vec![(0u128, 0u128); b * t * b * t];
+
+    for total in (0..=2 * (t - 1)).rev() {
+        for sa in total.saturating_sub(t - 1)..=total.min(t - 1) {
+            let sb = total - sa;
+            for pa in 0..b {
+                for pb in 0..b {
+                    let mut mover = 0;
+                    let mut other = 0;
+                    for &(roll, n) in &distribution {
+                        let (pos, score) = rules.update((pa as u32 + 1, sa as u32), roll);
+                        if score >= rules.target {
+                            mover = add(mover, n, 1)?;
+                        } else {
+                            // now it's the other player's turn
+                            let (w_other, w_mover) = table[index(pb, sb, pos as usize - 1, score as usize)];
+                            mover = add(mover, n, w_mover)?;
+                            other = add(other, n, w_other)?;
+                        }
+                    }
+                    table[index(pa, sa, pb, sb)] = (mover, other);
+                }
+            }
+        }
+    }
+    Ok(table[index(p1 as usize - 1, 0, p2 as usize - 1, 0)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_update(){
+        let rules = GameRules::dirac();
+        let res = rules.update((1, 10), 4);
+        assert_eq!(res, (5, 15));
+
+        let res = rules.update((9, 2), 4);
+        assert_eq!(res, (3, 5));
+    }
+
+    #[test]
+    fn test_distribution() {
+        let d = GameRules::dirac().roll_distribution();
+        assert_eq!(d, Ok(vec![0, 0, 0, 1, 3, 6, 7, 6, 3, 1]));
+        let rules = GameRules::new(10, 6, 2, 21).unwrap();
+        assert_eq!(rules.roll_distribution().unwrap().iter().sum::<u64>(), 36);
+        // the middle total of 70 coin tosses comes up more than 2^64 ways
+        let rules = GameRules::new(10, 2, 70, 21).unwrap();
+        assert_eq!(rules.roll_distribution(), Err(RulesError::Overflow));
+    }
+
+    #[test]
+    fn test_parse() {
+        let s = "Player 1 starting position: 4\nPlayer 2 starting position: 8\n";
+        assert_eq!(parse_positions(s, 10), Ok((4, 8)));
+        assert_eq!(parse_positions("Player 1 starting position: 4", 10), Err(ParseError::MissingPlayer));
+        assert_eq!(
+            parse_positions("Player 1 starting position: x\n", 10),
+            Err(ParseError::BadLine(1, "Player 1 starting position: x".to_string()))
+        );
+        assert_eq!(parse_positions(s, 6), Err(ParseError::OffBoard(2, 8)));
+        assert_eq!(parse_positions("Player 1 starting position: 0\n", 10), Err(ParseError::OffBoard(1, 0)));
+    }
+
+    #[test]
+    fn test_example() {
+        let result = play_deterministic(&GameRules::deterministic(), 4, 8);
+        assert_eq!(result, Ok(DeterministicResult { winner: 1, losing_score: 745, rolls: 993 }));
+
+        assert_eq!(play_dirac(&GameRules::dirac(), 4, 8), Ok((444356092776315, 341960390180808)));
+    }
+
+    #[test]
+    fn test_one_faced_die() {
+        // with only one face there's a single universe,
+        // so both modes have to agree on the winner
+        for target in [21, 100, 200] {
+            let rules = GameRules::new(7, 1, 3, target).unwrap();
+            let expected = match play_deterministic(&rules, 2, 5).unwrap().winner {
+                1 => (1, 0),
+                _ => (0, 1)
+            };
+            assert_eq!(play_dirac(&rules, 2, 5), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_bad_rules() {
+        assert_eq!(GameRules::new(0, 3, 3, 21), Err(RulesError::ZeroBoard));
+        assert_eq!(GameRules::new(10, 0, 3, 21), Err(RulesError::ZeroFaces));
+        assert_eq!(GameRules::new(10, 3, 0, 21), Err(RulesError::ZeroRolls));
+        assert_eq!(GameRules::new(10, 3, 3, 0), Err(RulesError::ZeroTarget));
+        assert_eq!(GameRules::new(10, 1 << 16, 2, 21), Err(RulesError::TurnTooBig(1 << 16, 2)));
+        assert_eq!(GameRules::new(10, u32::MAX, u32::MAX, 21), Err(RulesError::TurnTooBig(u32::MAX, u32::MAX)));
+
+        let rules = GameRules::dirac();
+        assert_eq!(play_dirac(&rules, 0, 4), Err(RulesError::OffBoard(0)));
+        assert_eq!(play_deterministic(&rules, 4, 11), Err(RulesError::OffBoard(11)));
+
+        let huge = GameRules::new(10, 3, 3, 60).unwrap();
+        assert_eq!(play_dirac(&huge, 4, 8), Err(RulesError::Overflow));
+    }
+}
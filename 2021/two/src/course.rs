@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CourseError {
+    UnknownCommand(usize, String),
+    MissingAmount(usize, String),
+    BadAmount(usize, String),
+    // a command like `surface` that doesn't take an amount was given one
+    UnexpectedAmount(usize, String),
+    BadRule(usize, String),
+    // more words on a line than the command takes
+    BadLine(usize, String),
+    // a position, depth or aim too big for an i64
+    Overflow(usize)
+}
+
+impl fmt::Display for CourseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CourseError::UnknownCommand(n, c) => write!(f, "line {}: unknown command '{}'", n, c),
+            CourseError::MissingAmount(n, c) => write!(f, "line {}: '{}' needs an amount", n, c),
+            CourseError::BadAmount(n, a) => write!(f, "line {}: '{}' isn't a whole number", n, a),
+            CourseError::UnexpectedAmount(n, c) => write!(f, "line {}: '{}' doesn't take an amount", n, c),
+            CourseError::BadRule(n, r) => write!(f, "rule {}: can't read '{}'", n, r),
+            CourseError::BadLine(n, l) => write!(f, "line {}: too many words in '{}'", n, l),
+            CourseError::Overflow(n) => write!(f, "line {}: the submarine has gone further than an i64 can count", n)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Horizontal,
+    Depth,
+    Aim
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Forward(i64),
+    Up(i64),
+    Down(i64),
+    // puts a value straight into the state, whatever the model
+    Set(Field, i64)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct State {
+    pub horizontal: i64,
+    pub depth: i64,
+    pub aim: i64
+}
+
+impl State {
+    // wide enough that any two i64s fit
+    pub fn product(&self) -> i128 {
+        self.horizontal as i128 * self.depth as i128
+    }
+}
+
+/// What forward, up and down mean. Each gives the new state,
+/// or None if a number in it would overflow.
+pub trait MovementModel {
+    fn forward(&self, state: State, x: i64) -> Option<State>;
+    fn up(&self, state: State, x: i64) -> Option<State>;
+    fn down(&self, state: State, x: i64) -> Option<State>;
+
+    fn apply(&self, state: State, command: Command) -> Option<State> {
+        match command {
+            Command::Forward(x) => self.forward(state, x),
+            Command::Up(x) => self.up(state, x),
+            Command::Down(x) => self.down(state, x),
+            Command::Set(Field::Horizontal, v) => Some(State { horizontal: v, ..state }),
+            Command::Set(Field::Depth, v) => Some(State { depth: v, ..state }),
+            Command::Set(Field::Aim, v) => Some(State { aim: v, ..state })
+        }
+    }
+}
+
+/// Part one: up and down change the depth directly.
+pub struct Direct;
+
+impl MovementModel for Direct {
+    fn forward(&self, state: State, x: i64) -> Option<State> {
+        Some(State { horizontal: state.horizontal.checked_add(x)?, ..state })
+    }
+    fn up(&self, state: State, x: i64) -> Option<State> {
+        Some(State { depth: state.depth.checked_sub(x)?, ..state })
+    }
+    fn down(&self, state: State, x: i64) -> Option<State> {
+        Some(State { depth: state.depth.checked_add(x)?, ..state })
+    }
+}
+
+/// Part two: up and down turn the submarine and forward follows the aim.
+pub struct Aimed;
+
+impl MovementModel for Aimed {
+    fn forward(&self, state: State, x: i64) -> Option<State> {
+        Some(State {
+            horizontal: state.horizontal.checked_add(x)?,
+            depth: state.depth.checked_add(x.checked_mul(state.aim)?)?,
+            ..state
+        })
+    }
+    fn up(&self, state: State, x: i64) -> Option<State> {
+        Some(State { aim: state.aim.checked_sub(x)?, ..state })
+    }
+    fn down(&self, state: State, x: i64) -> Option<State> {
+        Some(State { aim: state.aim.checked_add(x)?, ..state })
+    }
+}
+
+// One part of what a command does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    // a built in command with the amount multiplied by this
+    Forward(i64),
+    Up(i64),
+    Down(i64),
+    Set(Field, i64)
+}
+
+/// One line of the course, already turned into commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub line: usize,
+    pub commands: Vec<Command>
+}
+
+///
+/// The commands the submarine understands. Beyond forward, up and down
+/// new ones are defined one per line, as a name, a colon, and actions
+/// separated by semicolons. An action is a built in command with a
+/// multiplier for the amount, or a field set to a value:
+///
+/// ```text
+/// back: forward -1
+/// surface: depth = 0; aim = 0
+/// ```
+///
+/// A command with any built in action takes an amount, others don't.
+///
+#[derive(Debug, Clone)]
+pub struct Rules {
+    commands: HashMap<String, Vec<Action>>
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            commands: HashMap::from([
+                ("forward".to_string(), vec![Action::Forward(1)]),
+                ("up".to_string(), vec![Action::Up(1)]),
+                ("down".to_string(), vec![Action::Down(1)])
+            ])
+        }
+    }
+}
+
+fn parse_action(s: &str) -> Option<Action> {
+    if let Some((field, value)) = s.split_once('=') {
+        let field = match field.trim() {
+            "horizontal" => Field::Horizontal,
+            "depth" => Field::Depth,
+            "aim" => Field::Aim,
+            _ => return None
+        };
+        return Some(Action::Set(field, value.trim().parse().ok()?))
+    }
+    let (command, factor) = s.trim().split_once(' ')?;
+    let factor = factor.trim().parse().ok()?;
+    match command {
+        "forward" => Some(Action::Forward(factor)),
+        "up" => Some(Action::Up(factor)),
+        "down" => Some(Action::Down(factor)),
+        _ => None
+    }
+}
+
+impl FromStr for Rules {
+    type Err = CourseError;
+
+    /// The standard commands plus those defined in `s`. Lines starting with # are comments.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Rules::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            let bad = || CourseError::BadRule(i + 1, line.to_string());
+            let (name, actions) = line.split_once(':').ok_or_else(bad)?;
+            let actions = actions.split(';').map(parse_action).collect::<Option<Vec<Action>>>().ok_or_else(bad)?;
+            if name.trim().is_empty() || name.contains(char::is_whitespace) {
+                return Err(bad())
+            }
+            rules.commands.insert(name.trim().to_string(), actions);
+        }
+        Ok(rules)
+    }
+}
+
+impl Rules {
+    /// Reads a course, one command per line.
+    pub fn parse_course(&self, s: &str) -> Result<Vec<Instruction>, CourseError> {
+        s.lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| self.parse_line(i + 1, l))
+            .collect()
+    }
+
+    fn parse_line(&self, line: usize, s: &str) -> Result<Instruction, CourseError> {
+        let mut words = s.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let actions = self.commands.get(name).ok_or_else(|| CourseError::UnknownCommand(line, name.to_string()))?;
+        let takes_amount = actions.iter().any(|a| !matches!(a, Action::Set(..)));
+        let amount = match (words.next(), takes_amount) {
+            (Some(a), true) => a.parse::<i64>().map_err(|_| CourseError::BadAmount(line, a.to_string()))?,
+            (None, true) => return Err(CourseError::MissingAmount(line, name.to_string())),
+            (Some(_), false) => return Err(CourseError::UnexpectedAmount(line, name.to_string())),
+            (None, false) => 0
+        };
+        if words.next().is_some() {
+            return Err(CourseError::BadLine(line, s.trim().to_string()))
+        }
+        let times = |k: i64| k.checked_mul(amount).ok_or(CourseError::Overflow(line));
+        let commands = actions.iter()
+            .map(|a| match *a {
+                Action::Forward(k) => times(k).map(Command::Forward),
+                Action::Up(k) => times(k).map(Command::Up),
+                Action::Down(k) => times(k).map(Command::Down),
+                Action::Set(field, v) => Ok(Command::Set(field, v))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Instruction { line, commands })
+    }
+}
+
+/// Runs the commands and returns where the submarine ends up, or None if it overflows.
+pub fn run(model: &impl MovementModel, commands: &[Command]) -> Option<State> {
+    run_from(model, State::default(), commands)
+}
+
+/// The state after each instruction, in order.
+pub fn trace(model: &impl MovementModel, course: &[Instruction]) -> Result<Vec<State>, CourseError> {
+    let mut state = State::default();
+    course.iter()
+        .map(|instruction| {
+            state = run_from(model, state, &instruction.commands).ok_or(CourseError::Overflow(instruction.line))?;
+            Ok(state)
+        })
+        .collect()
+}
+
+fn run_from(model: &impl MovementModel, state: State, commands: &[Command]) -> Option<State> {
+    commands.iter().try_fold(state, |state, &c| model.apply(state, c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+
+    #[test]
+    fn test_trace() {
+        let course = Rules::default().parse_course(EXAMPLE).unwrap();
+        let direct = trace(&Direct, &course).unwrap();
+        assert_eq!(direct.last().unwrap().product(), 150);
+        let aimed = trace(&Aimed, &course).unwrap();
+        assert_eq!(aimed[2], State { horizontal: 13, depth: 40, aim: 5 });
+        assert_eq!(aimed.last().unwrap().product(), 900);
+    }
+
+    #[test]
+    fn test_rules() {
+        let rules:Rules = "# extra commands\nback: forward -1\nsurface: depth = 0; aim = 0".parse().unwrap();
+        let course = rules.parse_course("down 4\nforward 3\nback 1\nsurface\nforward 1").unwrap();
+        let states = trace(&Aimed, &course).unwrap();
+        assert_eq!(states[2], State { horizontal: 2, depth: 8, aim: 4 });
+        assert_eq!(states[3], State { horizontal: 2, depth: 0, aim: 0 });
+        assert_eq!(states[4], State { horizontal: 3, depth: 0, aim: 0 });
+
+        assert_eq!("back forward -1".parse::<Rules>().unwrap_err(), CourseError::BadRule(1, "back forward -1".to_string()));
+        assert_eq!("dive: sideways 2".parse::<Rules>().unwrap_err(), CourseError::BadRule(1, "dive: sideways 2".to_string()));
+    }
+
+    #[test]
+    fn test_bad_lines() {
+        let rules:Rules = "surface: depth = 0".parse().unwrap();
+        let err = |s: &str| rules.parse_course(s).unwrap_err();
+        assert_eq!(err("forward 1\nsideways 2"), CourseError::UnknownCommand(2, "sideways".to_string()));
+        assert_eq!(err("forward"), CourseError::MissingAmount(1, "forward".to_string()));
+        assert_eq!(err("up x"), CourseError::BadAmount(1, "x".to_string()));
+        assert_eq!(err("\nsurface 3"), CourseError::UnexpectedAmount(2, "surface".to_string()));
+        assert_eq!(err("up 3 down"), CourseError::BadLine(1, "up 3 down".to_string()));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let rules:Rules = "dive: down 4".parse().unwrap();
+        let big = i64::MAX / 2;
+        assert_eq!(rules.parse_course(&format!("dive {}", big)).unwrap_err(), CourseError::Overflow(1));
+
+        let course = rules.parse_course(&format!("down {}\nforward 1\nforward 3", big)).unwrap();
+        assert_eq!(trace(&Direct, &course).unwrap()[2].product(), 4 * big as i128);
+        assert_eq!(trace(&Aimed, &course), Err(CourseError::Overflow(3)));
+        assert_eq!(run(&Direct, &[Command::Up(i64::MAX), Command::Up(2)]), None);
+    }
+}
@@ -0,0 +1,375 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameError {
+    // the beats relation doesn't decide every pair of moves exactly once
+    BadRelation(String),
+    BadLine { line: usize, text: String },
+    UnknownMove { line: usize, text: String },
+    UnknownOutcome { line: usize, text: String },
+    // a score for every move, no more and no fewer
+    BadScores { moves: usize, scores: usize },
+    // the same letter used twice in one column of the guide
+    BadGuide(String),
+    // nothing the game allows ends the round the way the guide asks
+    NoResponse { line: usize, theirs: String, outcome: Outcome }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::BadRelation(why) => write!(f, "not a fair game: {}", why),
+            GameError::BadLine { line, text } => write!(f, "line {}: expected two columns in '{}'", line, text),
+            GameError::UnknownMove { line, text } => write!(f, "line {}: '{}' isn't a move", line, text),
+            GameError::UnknownOutcome { line, text } => write!(f, "line {}: '{}' isn't an outcome", line, text),
+            GameError::BadScores { moves, scores } => write!(f, "{} moves need {} scores, not {}", moves, moves, scores),
+            GameError::BadGuide(why) => write!(f, "can't read the guide: {}", why),
+            GameError::NoResponse { line, theirs, outcome } => {
+                let result = match outcome {
+                    Outcome::Lose => "loses",
+                    Outcome::Draw => "draws",
+                    Outcome::Win => "wins"
+                };
+                write!(f, "line {}: no move {} against {}", line, result, theirs)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win
+}
+
+/// How the second column of the strategy guide is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // it's the move to play
+    Moves,
+    // it's how the round should end
+    Outcomes
+}
+
+/// Points for how a round ends. Each move's points are part of the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scoring {
+    pub lose: u32,
+    pub draw: u32,
+    pub win: u32
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Scoring { lose: 0, draw: 3, win: 6 }
+    }
+}
+
+impl Scoring {
+    pub fn points(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Lose => self.lose,
+            Outcome::Draw => self.draw,
+            Outcome::Win => self.win
+        }
+    }
+}
+
+///
+/// A game where each player picks one of N moves and a beats relation
+/// says who wins. Every pair of different moves has exactly one winner.
+///
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub moves: Vec<String>,
+    pub scores: Vec<u32>,
+    // beats[a][b] is true when a beats b
+    beats: Vec<Vec<bool>>
+}
+
+impl Game {
+    pub fn new(moves: &[&str], beats: &[(&str, &str)]) -> Result<Self, GameError> {
+        let n = moves.len();
+        let index = |name: &str| {
+            moves.iter()
+                .position(|m| *m == name)
+                .ok_or_else(|| GameError::BadRelation(format!("'{}' isn't one of the moves", name)))
+        };
+        if let Some(m) = moves.iter().enumerate().find_map(|(i, m)| moves[..i].contains(m).then_some(m)) {
+            return Err(GameError::BadRelation(format!("'{}' is one of the moves twice", m)))
+        }
+        let mut relation = vec![vec![false; n]; n];
+        for &(a, b) in beats {
+            let (a, b) = (index(a)?, index(b)?);
+            if a == b {
+                return Err(GameError::BadRelation(format!("{} can't beat itself", moves[a])))
+            }
+            relation[a][b] = true;
+        }
+        for a in 0..n {
+            for b in a + 1..n {
+                if relation[a][b] == relation[b][a] {
+                    return Err(GameError::BadRelation(format!("{} and {} need exactly one winner", moves[a], moves[b])))
+                }
+            }
+        }
+        Ok(Game {
+            moves: moves.iter().map(|m| m.to_string()).collect(),
+            scores: (1..=n as u32).collect(),
+            beats: relation
+        })
+    }
+
+    ///
+    /// The balanced game on an odd number of moves where each move
+    /// beats the one before it, the one three before, and so on round the circle.
+    /// Moves score 1, 2, 3... in the order given.
+    ///
+    pub fn cyclic(moves: &[&str]) -> Result<Self, GameError> {
+        let n = moves.len();
+        if n.is_multiple_of(2) {
+            return Err(GameError::BadRelation(format!("a cycle of {} moves has ties", n)))
+        }
+        let beats:Vec<(&str, &str)> = (0..n)
+            .flat_map(|a| (1..n).step_by(2).map(move |d| (moves[a], moves[(a + n - d) % n])))
+            .collect();
+        Game::new(moves, &beats)
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Game::cyclic(&["Rock", "Paper", "Scissors"]).unwrap()
+    }
+
+    pub fn rock_paper_scissors_spock_lizard() -> Self {
+        Game::cyclic(&["Rock", "Paper", "Scissors", "Spock", "Lizard"]).unwrap()
+    }
+
+    /// Gives each move its own score instead of 1, 2, 3...
+    pub fn with_scores(mut self, scores: &[u32]) -> Result<Self, GameError> {
+        if scores.len() != self.moves.len() {
+            return Err(GameError::BadScores { moves: self.moves.len(), scores: scores.len() })
+        }
+        self.scores = scores.to_vec();
+        Ok(self)
+    }
+
+    pub fn outcome(&self, ours: usize, theirs: usize) -> Outcome {
+        if ours == theirs {
+            Outcome::Draw
+        } else if self.beats[ours][theirs] {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    /// The move that gets `outcome` against `theirs`.
+    /// When several do, it's the one worth the most. There may be none,
+    /// nothing beats a move that beats everything else.
+    pub fn respond(&self, theirs: usize, outcome: Outcome) -> Option<usize> {
+        (0..self.moves.len())
+            .filter(|&m| self.outcome(m, theirs) == outcome)
+            .max_by_key(|&m| (self.scores[m], std::cmp::Reverse(m)))
+    }
+}
+
+/// The letters used in the strategy guide.
+#[derive(Debug, Clone)]
+pub struct Guide {
+    pub theirs: Vec<String>,
+    pub ours: Vec<String>,
+    // lose, draw, win
+    pub outcomes: [String; 3]
+}
+
+impl Default for Guide {
+    /// A B C for their moves, X Y Z for ours or for lose, draw and win.
+    fn default() -> Self {
+        Guide::new(&["A", "B", "C"], &["X", "Y", "Z"], ["X", "Y", "Z"]).unwrap()
+    }
+}
+
+impl Guide {
+    /// Each column needs different letters, but the same letter can
+    /// mean one thing in one column and another in the next.
+    pub fn new(theirs: &[&str], ours: &[&str], outcomes: [&str; 3]) -> Result<Self, GameError> {
+        for (column, letters) in [("their moves", theirs), ("our moves", ours), ("outcomes", &outcomes[..])] {
+            if let Some(l) = letters.iter().enumerate().find_map(|(i, l)| letters[..i].contains(l).then_some(l)) {
+                return Err(GameError::BadGuide(format!("'{}' is used twice for {}", l, column)))
+            }
+        }
+        Ok(Guide {
+            theirs: theirs.iter().map(|s| s.to_string()).collect(),
+            ours: ours.iter().map(|s| s.to_string()).collect(),
+            outcomes: outcomes.map(|s| s.to_string())
+        })
+    }
+}
+
+/// One round of the tournament and how it scored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub line: usize,
+    pub theirs: usize,
+    pub ours: usize,
+    pub outcome: Outcome,
+    pub move_points: u32,
+    pub outcome_points: u32
+}
+
+impl Round {
+    pub fn score(&self) -> u32 {
+        self.move_points + self.outcome_points
+    }
+}
+
+/// A game and how to read and score a strategy guide for it.
+#[derive(Debug, Clone)]
+pub struct Tournament {
+    pub game: Game,
+    pub guide: Guide,
+    pub scoring: Scoring
+}
+
+impl Tournament {
+    pub fn new(game: Game) -> Self {
+        Tournament { game, guide: Guide::default(), scoring: Scoring::default() }
+    }
+
+    /// Every round in the guide, scored.
+    pub fn rounds(&self, input: &str, strategy: Strategy) -> Result<Vec<Round>, GameError> {
+        input.lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| self.round(i + 1, l, strategy))
+            .collect()
+    }
+
+    fn round(&self, line: usize, text: &str, strategy: Strategy) -> Result<Round, GameError> {
+        let columns:Vec<&str> = text.split_whitespace().collect();
+        let [a, b] = columns[..] else {
+            return Err(GameError::BadLine { line, text: text.to_string() })
+        };
+        let find = |letters: &[String], s: &str| letters.iter().position(|l| l == s);
+        let unknown_move = |s: &str| GameError::UnknownMove { line, text: s.to_string() };
+        let theirs = find(&self.guide.theirs, a)
+            .filter(|&m| m < self.game.moves.len())
+            .ok_or_else(|| unknown_move(a))?;
+
+        let ours = match strategy {
+            Strategy::Moves => find(&self.guide.ours, b)
+                .filter(|&m| m < self.game.moves.len())
+                .ok_or_else(|| unknown_move(b))?,
+            Strategy::Outcomes => {
+                let outcome = match find(&self.guide.outcomes, b) {
+                    Some(0) => Outcome::Lose,
+                    Some(1) => Outcome::Draw,
+                    Some(_) => Outcome::Win,
+                    None => return Err(GameError::UnknownOutcome { line, text: b.to_string() })
+                };
+                self.game.respond(theirs, outcome)
+                    .ok_or_else(|| GameError::NoResponse { line, theirs: self.game.moves[theirs].clone(), outcome })?
+            }
+        };
+        let outcome = self.game.outcome(ours, theirs);
+        Ok(Round {
+            line,
+            theirs,
+            ours,
+            outcome,
+            move_points: self.game.scores[ours],
+            outcome_points: self.scoring.points(outcome)
+        })
+    }
+
+    pub fn total(&self, input: &str, strategy: Strategy) -> Result<u32, GameError> {
+        Ok(self.rounds(input, strategy)?.iter().map(|r| r.score()).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "A Y\nB X\nC Z";
+
+    #[test]
+    fn test_example() {
+        let tournament = Tournament::new(Game::rock_paper_scissors());
+        assert_eq!(tournament.total(EXAMPLE, Strategy::Moves), Ok(15));
+        assert_eq!(tournament.total(EXAMPLE, Strategy::Outcomes), Ok(12));
+
+        let rounds = tournament.rounds(EXAMPLE, Strategy::Outcomes).unwrap();
+        assert_eq!(rounds[2], Round { line: 3, theirs: 2, ours: 0, outcome: Outcome::Win, move_points: 1, outcome_points: 6 });
+    }
+
+    #[test]
+    fn test_spock_lizard() {
+        let game = Game::rock_paper_scissors_spock_lizard();
+        let (rock, paper, scissors, spock, lizard) = (0, 1, 2, 3, 4);
+        assert_eq!(game.outcome(spock, scissors), Outcome::Win);
+        assert_eq!(game.outcome(spock, rock), Outcome::Win);
+        assert_eq!(game.outcome(lizard, spock), Outcome::Win);
+        assert_eq!(game.outcome(lizard, paper), Outcome::Win);
+        assert_eq!(game.outcome(rock, lizard), Outcome::Win);
+        assert_eq!(game.outcome(paper, lizard), Outcome::Lose);
+        // scissors and rock both beat lizard, rock is worth less
+        assert_eq!(game.respond(lizard, Outcome::Win), Some(scissors));
+
+        let mut tournament = Tournament::new(game);
+        tournament.guide = Guide::new(&["A", "B", "C", "D", "E"], &["V", "W", "X", "Y", "Z"], ["L", "D", "W"]).unwrap();
+        tournament.scoring = Scoring { lose: 0, draw: 1, win: 2 };
+        assert_eq!(tournament.total("D Z\nE W", Strategy::Moves), Ok(5 + 2 + 2));
+        // scissors and lizard both lose to rock, lizard is worth more
+        assert_eq!(tournament.total("A L", Strategy::Outcomes), Ok(5));
+    }
+
+    #[test]
+    fn test_bad_input() {
+        let tournament = Tournament::new(Game::rock_paper_scissors());
+        assert_eq!(
+            tournament.total("A Y\nB Q", Strategy::Outcomes),
+            Err(GameError::UnknownOutcome { line: 2, text: "Q".to_string() })
+        );
+        assert_eq!(
+            tournament.total("A Y\n\nD X", Strategy::Moves),
+            Err(GameError::UnknownMove { line: 3, text: "D".to_string() })
+        );
+        assert_eq!(tournament.total("AY", Strategy::Moves), Err(GameError::BadLine { line: 1, text: "AY".to_string() }));
+
+        assert!(Game::cyclic(&["a", "b", "c", "d"]).is_err());
+        assert!(Game::new(&["a", "b", "c"], &[("a", "b"), ("b", "c")]).is_err());
+        assert_eq!(Game::rock_paper_scissors().with_scores(&[1, 2]).unwrap_err(), GameError::BadScores { moves: 3, scores: 2 });
+
+        // scissors is now worth the most, so it's the losing answer to rock
+        let game = Game::rock_paper_scissors().with_scores(&[1, 2, 9]).unwrap();
+        assert_eq!(game.respond(0, Outcome::Lose), Some(2));
+
+        assert_eq!(
+            Game::new(&["a", "b", "a"], &[("a", "b")]).unwrap_err(),
+            GameError::BadRelation("'a' is one of the moves twice".to_string())
+        );
+        assert_eq!(
+            Guide::new(&["A", "B", "A"], &["X", "Y", "Z"], ["X", "Y", "Z"]).unwrap_err(),
+            GameError::BadGuide("'A' is used twice for their moves".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_response() {
+        // a beats both the others, so nothing beats a
+        let game = Game::new(&["a", "b", "c"], &[("a", "b"), ("a", "c"), ("b", "c")]).unwrap();
+        assert_eq!(game.respond(0, Outcome::Win), None);
+        assert_eq!(game.respond(2, Outcome::Lose), None);
+        assert_eq!(game.respond(1, Outcome::Win), Some(0));
+
+        let tournament = Tournament::new(game);
+        // c loses to b, and b is the best scoring move that beats c
+        assert_eq!(tournament.total("B X\nC Z", Strategy::Outcomes), Ok(3 + 8));
+        assert_eq!(
+            tournament.total("B X\nA Z", Strategy::Outcomes),
+            Err(GameError::NoResponse { line: 2, theirs: "a".to_string(), outcome: Outcome::Win })
+        );
+    }
+}
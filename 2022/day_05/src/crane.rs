@@ -0,0 +1,267 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CraneError {
+    BadDrawing { line: usize, text: String },
+    NoStackNames,
+    BadInstruction { line: usize, text: String },
+    UnknownStack { line: usize, name: String },
+    NotEnoughCrates { line: usize, text: String, wanted: usize, available: usize },
+    ZeroCapacity
+}
+
+impl fmt::Display for CraneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CraneError::BadDrawing { line, text } => write!(f, "line {}: can't read the drawing '{}'", line, text),
+            CraneError::NoStackNames => write!(f, "the drawing has no line of stack names"),
+            CraneError::BadInstruction { line, text } => write!(f, "line {}: can't read instruction '{}'", line, text),
+            CraneError::UnknownStack { line, name } => write!(f, "line {}: there's no stack {}", line, name),
+            CraneError::NotEnoughCrates { line, text, wanted, available } => {
+                write!(f, "line {}: '{}' wants {} crates but there are only {}", line, text, wanted, available)
+            },
+            CraneError::ZeroCapacity => write!(f, "a crane has to lift at least one crate")
+        }
+    }
+}
+
+/// How many crates a crane picks up at once. Crates lifted together keep their order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crane {
+    // one at a time
+    CrateMover9000,
+    // the whole lot
+    CrateMover9001,
+    UpTo(usize)
+}
+
+impl Crane {
+    fn capacity(&self) -> Result<usize, CraneError> {
+        match self {
+            Crane::CrateMover9000 => Ok(1),
+            Crane::CrateMover9001 => Ok(usize::MAX),
+            Crane::UpTo(0) => Err(CraneError::ZeroCapacity),
+            Crane::UpTo(k) => Ok(*k)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub line: usize,
+    pub text: String,
+    pub count: usize,
+    // indexes into the stacks
+    pub from: usize,
+    pub to: usize
+}
+
+///
+/// The stacks of crates, bottom first, and the names written under them.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dock {
+    pub names: Vec<String>,
+    pub stacks: Vec<Vec<String>>
+}
+
+// Each word in a line with the column it starts in
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut found = vec![];
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(i),
+            (true, Some(s)) => {
+                found.push((s, &line[s..i]));
+                start = None;
+            },
+            _ => {}
+        }
+    }
+    found
+}
+
+impl Dock {
+    ///
+    /// Reads the drawing: rows of `[label]` crates over a line of stack names.
+    /// Labels can be any length and lines can stop short. A crate goes on the
+    /// stack whose name is under it, or the nearest one if none is.
+    ///
+    pub fn from_drawing(lines: &[&str]) -> Result<Self, CraneError> {
+        let (names_line, rows) = lines.split_last().ok_or(CraneError::NoStackNames)?;
+        let names = words(names_line);
+        if names.is_empty() || names.iter().any(|(_, w)| w.starts_with('[')) {
+            return Err(CraneError::NoStackNames)
+        }
+        let centre = |(start, word): (usize, &str)| 2 * start + word.len();
+        let mut stacks = vec![vec![]; names.len()];
+
+        for (i, row) in rows.iter().enumerate().rev() {
+            for (start, word) in words(row) {
+                let label = word.strip_prefix('[')
+                    .and_then(|w| w.strip_suffix(']'))
+                    .filter(|l| !l.is_empty())
+                    .ok_or_else(|| CraneError::BadDrawing { line: i + 1, text: row.to_string() })?;
+                let stack = (0..names.len())
+                    .min_by_key(|&s| centre(names[s]).abs_diff(centre((start, word))))
+                    .unwrap();
+                stacks[stack].push(label.to_string());
+            }
+        }
+        Ok(Dock { names: names.iter().map(|(_, w)| w.to_string()).collect(), stacks })
+    }
+
+    fn stack(&self, line: usize, name: &str) -> Result<usize, CraneError> {
+        self.names.iter().position(|n| n == name).ok_or_else(|| CraneError::UnknownStack { line, name: name.to_string() })
+    }
+
+    /// Reads `move N from A to B` where A and B are stack names.
+    pub fn parse_move(&self, line: usize, text: &str) -> Result<Move, CraneError> {
+        let bad = || CraneError::BadInstruction { line, text: text.to_string() };
+        let w:Vec<&str> = text.split_whitespace().collect();
+        let ["move", count, "from", from, "to", to] = w[..] else {
+            return Err(bad())
+        };
+        Ok(Move {
+            line,
+            text: text.trim().to_string(),
+            count: count.parse().map_err(|_| bad())?,
+            from: self.stack(line, from)?,
+            to: self.stack(line, to)?
+        })
+    }
+
+    /// Makes one move, or leaves everything as it was if it can't be done.
+    pub fn step(&mut self, m: &Move, crane: Crane) -> Result<(), CraneError> {
+        let capacity = crane.capacity()?;
+        let available = self.stacks[m.from].len();
+        if m.count > available {
+            return Err(CraneError::NotEnoughCrates { line: m.line, text: m.text.clone(), wanted: m.count, available })
+        }
+        let mut left = m.count;
+        while left > 0 {
+            let lift = left.min(capacity);
+            let from = &mut self.stacks[m.from];
+            let mut crates = from.split_off(from.len() - lift);
+            self.stacks[m.to].append(&mut crates);
+            left -= lift;
+        }
+        Ok(())
+    }
+
+    /// Makes every move, calling `observe` after each one.
+    pub fn run(&mut self, moves: &[Move], crane: Crane, mut observe: impl FnMut(&Move, &Dock)) -> Result<(), CraneError> {
+        for m in moves {
+            self.step(m, crane)?;
+            observe(m, self);
+        }
+        Ok(())
+    }
+
+    /// The label on top of each stack, skipping empty ones.
+    pub fn tops(&self) -> String {
+        self.stacks.iter().filter_map(|s| s.last()).map(|s| s.as_str()).collect()
+    }
+}
+
+/// The whole puzzle input: the drawing, a blank line and then the moves.
+pub fn parse(s: &str) -> Result<(Dock, Vec<Move>), CraneError> {
+    let lines:Vec<&str> = s.lines().collect();
+    let blank = lines.iter().position(|l| l.trim().is_empty()).unwrap_or(lines.len());
+    let dock = Dock::from_drawing(&lines[..blank])?;
+    let moves = lines.iter()
+        .enumerate()
+        .skip(blank + 1)
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| dock.parse_move(i + 1, l))
+        .collect::<Result<Vec<Move>, _>>()?;
+    Ok((dock, moves))
+}
+
+impl fmt::Display for Dock {
+    /// Draws the stacks the way the puzzle does, wide enough for the longest label.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label_width = self.stacks.iter().flatten().chain(&self.names).map(|l| l.chars().count()).max().unwrap_or(1);
+        let width = label_width + 2;
+        let height = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+
+        for row in (0..height).rev() {
+            let cells:Vec<String> = self.stacks.iter()
+                .map(|s| match s.get(row) {
+                    Some(label) => format!("{:^width$}", format!("[{}]", label), width = width),
+                    None => " ".repeat(width)
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" ").trim_end())?;
+        }
+        let names:Vec<String> = self.names.iter().map(|n| format!("{:^width$}", n, width = width)).collect();
+        write!(f, "{}", names.join(" ").trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    fn finish(crane: Crane) -> Dock {
+        let (mut dock, moves) = parse(EXAMPLE).unwrap();
+        dock.run(&moves, crane, |_, _| {}).unwrap();
+        dock
+    }
+
+    #[test]
+    fn test_cranes() {
+        assert_eq!(finish(Crane::CrateMover9000).tops(), "CMZ");
+        assert_eq!(finish(Crane::CrateMover9001).tops(), "MCD");
+        assert_eq!(finish(Crane::UpTo(2)).tops(), "MCZ");
+        assert_eq!(finish(Crane::UpTo(2)).stacks[2], vec!["P", "N", "D", "Z"]);
+    }
+
+    #[test]
+    fn test_render() {
+        let (mut dock, moves) = parse(EXAMPLE).unwrap();
+        assert_eq!(dock.to_string(), "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3");
+
+        let mut frames = vec![];
+        dock.run(&moves, Crane::CrateMover9000, |_, d| frames.push(d.to_string())).unwrap();
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[1], "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3");
+    }
+
+    #[test]
+    fn test_long_labels() {
+        let (dock, _) = parse("[ab]\n[cd] [e]\n 1    2\n").unwrap();
+        assert_eq!(dock.stacks, vec![vec!["cd", "ab"], vec!["e"]]);
+        assert_eq!(dock.to_string(), "[ab]\n[cd] [e]\n 1    2");
+        assert_eq!(dock.tops(), "abe");
+    }
+
+    #[test]
+    fn test_bad_moves() {
+        let (mut dock, _) = parse(EXAMPLE).unwrap();
+        let m = dock.parse_move(7, "move 3 from 3 to 1").unwrap();
+        assert_eq!(
+            dock.step(&m, Crane::CrateMover9001),
+            Err(CraneError::NotEnoughCrates { line: 7, text: "move 3 from 3 to 1".to_string(), wanted: 3, available: 1 })
+        );
+        assert_eq!(dock.tops(), "NDP");
+        assert_eq!(dock.parse_move(8, "move 1 from 4 to 1"), Err(CraneError::UnknownStack { line: 8, name: "4".to_string() }));
+        assert_eq!(
+            dock.parse_move(9, "move one from 1 to 2"),
+            Err(CraneError::BadInstruction { line: 9, text: "move one from 1 to 2".to_string() })
+        );
+        assert_eq!(dock.step(&dock.parse_move(1, "move 1 from 1 to 2").unwrap(), Crane::UpTo(0)), Err(CraneError::ZeroCapacity));
+        assert_eq!(parse("[A] B\n 1  2").unwrap_err(), CraneError::BadDrawing { line: 1, text: "[A] B".to_string() });
+    }
+}
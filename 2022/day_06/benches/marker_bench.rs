@@ -0,0 +1,33 @@
+//! Benchmarks
+#[macro_use]
+extern crate bencher;
+extern crate day_06;
+use self::bencher::Bencher;
+
+use day_06::marker;
+
+// a long stream from 13 letters so the only 14 letter marker is at the end
+fn make_data() -> String {
+    let mut s:String = (0..100000).map(|i| (b'a' + ((i * 7 + i / 13) % 13) as u8) as char).collect();
+    s.push_str("nopqrstuvwxyza");
+    s
+}
+
+fn bench_counts(b: &mut Bencher) {
+    let data = make_data();
+
+    b.iter(|| marker::find_first(&data, 14))
+}
+
+fn bench_hashset(b: &mut Bencher) {
+    let data = make_data();
+
+    b.iter(|| marker::find_first_hashset(&data, 14))
+}
+
+benchmark_group!(
+    benches,
+    bench_counts,
+    bench_hashset
+);
+benchmark_main!(benches);
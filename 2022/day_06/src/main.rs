@@ -1,32 +1,17 @@
-use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
+use day_06::marker::find_first;
 
 static DATA:&str = "data.txt";
 
-fn find_first_marker(s:&str, marker_size:usize) -> usize {
-    let tokens:Vec<char> = s.chars().collect();
-    let mut h = HashSet::<char>::with_capacity(marker_size);
-
-    for (i, w) in tokens.windows(marker_size).enumerate() {
-        h.extend(w);
-        if h.len() == marker_size {
-            return i + marker_size
-        }
-        h.clear()
-    }
-
-    panic!("No marker found!")
-}
-
 fn main() {
     let path = Path::new(DATA);
     let s = fs::read_to_string(path).expect("could not open the data file");
 
-    let part_one = find_first_marker(&s, 4);   
+    let part_one = find_first(&s, 4).expect("No marker found!");
     println!("Part one: {}", part_one);
 
-    let part_two = find_first_marker(&s, 14);   
+    let part_two = find_first(&s, 14).expect("No marker found!");
     println!("Part two: {}", part_two);
 
 }
@@ -39,38 +24,38 @@ mod tests {
     #[test]
     fn test_start_of_packet() {
         let s = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        let loc = find_first_marker(s, 4);
-        assert_eq!(loc, 5);
+        let loc = find_first(s, 4);
+        assert_eq!(loc, Some(5));
 
         let s = "nppdvjthqldpwncqszvftbrmjlhg";
-        let loc = find_first_marker(s, 4);
-        assert_eq!(loc, 6);
+        let loc = find_first(s, 4);
+        assert_eq!(loc, Some(6));
         
         let s = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        let loc = find_first_marker(s, 4);
-        assert_eq!(loc, 10);
+        let loc = find_first(s, 4);
+        assert_eq!(loc, Some(10));
 
         let s = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-        let loc = find_first_marker(s, 4);
-        assert_eq!(loc, 11);
+        let loc = find_first(s, 4);
+        assert_eq!(loc, Some(11));
     }
 
     #[test]
     fn test_start_of_message() {
         let s = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        let loc = find_first_marker(s, 14);
-        assert_eq!(loc, 23);
+        let loc = find_first(s, 14);
+        assert_eq!(loc, Some(23));
 
         let s = "nppdvjthqldpwncqszvftbrmjlhg";
-        let loc = find_first_marker(s, 14);
-        assert_eq!(loc, 23);
+        let loc = find_first(s, 14);
+        assert_eq!(loc, Some(23));
         
         let s = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        let loc = find_first_marker(s, 14);
-        assert_eq!(loc, 29);
+        let loc = find_first(s, 14);
+        assert_eq!(loc, Some(29));
 
         let s = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-        let loc = find_first_marker(s, 14);
-        assert_eq!(loc, 26);
+        let loc = find_first(s, 14);
+        assert_eq!(loc, Some(26));
     }
-}
\ No newline at end of file
+}
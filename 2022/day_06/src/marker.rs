@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead};
+
+///
+/// The last `size` bytes of a stream with how many times each byte
+/// turns up in them, and how many byte values turn up more than once.
+/// Sliding on by one only touches the byte coming in and the one going
+/// out, so checking every window is O(n) whatever the size.
+///
+#[derive(Debug)]
+pub struct Window {
+    size: usize,
+    ring: Vec<u8>,
+    counts: [u32; 256],
+    duplicates: usize,
+    seen: usize
+}
+
+impl Window {
+    pub fn new(size: usize) -> Self {
+        Window { size, ring: Vec::with_capacity(size), counts: [0; 256], duplicates: 0, seen: 0 }
+    }
+
+    /// Adds a byte and says whether the window is now full and all different.
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.size == 0 {
+            return false
+        }
+        if self.ring.len() < self.size {
+            self.ring.push(byte);
+        } else {
+            let slot = self.seen % self.size;
+            let out = self.ring[slot] as usize;
+            self.counts[out] -= 1;
+            if self.counts[out] == 1 {
+                self.duplicates -= 1;
+            }
+            self.ring[slot] = byte;
+        }
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+        self.seen += 1;
+        self.ring.len() == self.size && self.duplicates == 0
+    }
+
+    /// How many bytes have gone in so far.
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+}
+
+/// How many bytes come before the end of the first marker, if there is one.
+/// Markers are made of distinct bytes, not characters, so two letters
+/// sharing a UTF-8 lead byte aren't distinct. There's never a marker of size 0.
+pub fn find_first(data: impl AsRef<[u8]>, size: usize) -> Option<usize> {
+    let mut window = Window::new(size);
+    data.as_ref().iter().position(|&b| window.push(b)).map(|i| i + 1)
+}
+
+/// Where every marker ends, overlapping ones included, counting bytes.
+pub fn find_all(data: impl AsRef<[u8]>, size: usize) -> Vec<usize> {
+    let mut window = Window::new(size);
+    data.as_ref()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &b)| window.push(b).then_some(i + 1))
+        .collect()
+}
+
+/// Like `find_first` but reads bytes from `reader` until a marker turns up.
+pub fn find_in_reader<R: BufRead>(mut reader: R, size: usize) -> io::Result<Option<usize>> {
+    let mut window = Window::new(size);
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(None)
+        }
+        if buffer.iter().any(|&b| window.push(b)) {
+            return Ok(Some(window.seen()))
+        }
+        let n = buffer.len();
+        reader.consume(n);
+    }
+}
+
+/// Fills a set from every window in turn. Kept to compare against.
+pub fn find_first_hashset(data: impl AsRef<[u8]>, size: usize) -> Option<usize> {
+    if size == 0 {
+        return None
+    }
+    let mut h = HashSet::<u8>::with_capacity(size);
+
+    for (i, w) in data.as_ref().windows(size).enumerate() {
+        h.extend(w);
+        if h.len() == size {
+            return Some(i + size)
+        }
+        h.clear()
+    }
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    static EXAMPLES: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26)
+    ];
+
+    #[test]
+    fn test_examples() {
+        for (s, packet, message) in EXAMPLES {
+            assert_eq!(find_first(s, 4), Some(packet));
+            assert_eq!(find_first(s, 14), Some(message));
+            assert_eq!(find_in_reader(Cursor::new(s), 14).unwrap(), Some(message));
+        }
+    }
+
+    #[test]
+    fn test_find_all() {
+        assert_eq!(find_all("aabcab", 3), vec![4, 5, 6]);
+        assert_eq!(find_all("abcd", 1), vec![1, 2, 3, 4]);
+        assert_eq!(find_first("aaaa", 2), None);
+        assert_eq!(find_first("abc", 0), None);
+        assert_eq!(find_in_reader(Cursor::new("abab"), 3).unwrap(), None);
+        // markers that straddle the reader's buffer
+        let s = "abcabcabcdefg";
+        assert_eq!(find_in_reader(io::BufReader::with_capacity(2, s.as_bytes()), 4).unwrap(), find_first(s, 4));
+    }
+
+    #[test]
+    fn test_against_hashset() {
+        // markers at the start, at the very end, straddling a repeat, and none at all
+        let cases = ["abcd", "aaaabcd", "abcabcabcabd", "abacadaeafag", "zzzzzz", "qwertyuiopasdfgh", ""];
+        for s in cases {
+            for size in 0..8 {
+                assert_eq!(find_first(s, size), find_first_hashset(s, size), "{} {}", s, size);
+            }
+        }
+        assert_eq!(find_all("abacabad", 3), vec![4, 6, 8]);
+    }
+
+    #[test]
+    fn test_bytes() {
+        // é and è share their first byte, so only three of the four bytes differ
+        assert_eq!("éè".len(), 4);
+        assert_eq!(find_first("éè", 2), Some(2));
+        assert_eq!(find_first("éè", 4), None);
+        assert_eq!(find_first_hashset("éè", 4), None);
+        assert_eq!(find_first([0u8, 255, 0, 1], 3), Some(4));
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+// Like the original solution, the tree is a vector of nodes that refer to
+// each other by index. Parents are always pushed before their children,
+// which lets sizes be totalled with a single backwards pass.
+pub const ROOT: usize = 0;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Dir(BTreeMap<String, usize>),
+    File(usize)
+}
+
+#[derive(Debug)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub kind: NodeKind
+}
+
+impl Node {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, NodeKind::Dir(_))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FsError {
+    // line numbers are 1-based to match an editor
+    UnknownCommand { line: usize, text: String },
+    BadListing { line: usize, text: String },
+    OutputWithoutLs { line: usize },
+    AboveRoot { line: usize },
+    NotADirectory { line: usize, path: String },
+    ConflictingSize { line: usize, path: String, old: usize, new: usize },
+    ConflictingKind { line: usize, path: String }
+}
+
+impl fmt::Display for FsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FsError::UnknownCommand { line, text } => write!(f, "line {}: unknown command '{}'", line, text),
+            FsError::BadListing { line, text } => write!(f, "line {}: can't parse listing '{}'", line, text),
+            FsError::OutputWithoutLs { line } => write!(f, "line {}: output before any ls", line),
+            FsError::AboveRoot { line } => write!(f, "line {}: cd .. from the root directory", line),
+            FsError::NotADirectory { line, path } => write!(f, "line {}: {} is not a directory", line, path),
+            FsError::ConflictingSize { line, path, old, new } => {
+                write!(f, "line {}: {} listed with size {} but was {}", line, path, new, old)
+            },
+            FsError::ConflictingKind { line, path } => {
+                write!(f, "line {}: {} listed as both a file and a directory", line, path)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct VirtualFs {
+    nodes: Vec<Node>,
+    sizes: Vec<usize>
+}
+
+impl VirtualFs {
+    pub fn new() -> VirtualFs {
+        let root = Node { name: "/".to_string(), parent: None, kind: NodeKind::Dir(BTreeMap::new()) };
+        VirtualFs { nodes: vec![root], sizes: vec![0] }
+    }
+
+    /// Replays a shell transcript of `cd` and `ls` commands into a new tree.
+    pub fn from_transcript(transcript: &str) -> Result<VirtualFs, FsError> {
+        let mut fs = VirtualFs::new();
+        let mut current = ROOT;
+        let mut listing = false;
+
+        for (i, l) in transcript.lines().enumerate() {
+            let line = i + 1;
+            if let Some(command) = l.strip_prefix("$ ") {
+                listing = false;
+                match command.split_once(' ') {
+                    Some(("cd", "/")) => current = ROOT,
+                    Some(("cd", "..")) => {
+                        current = fs.nodes[current].parent.ok_or(FsError::AboveRoot { line })?
+                    },
+                    Some(("cd", name)) => current = fs.mkdir(current, name, line)?,
+                    None if command == "ls" => listing = true,
+                    _ => return Err(FsError::UnknownCommand { line, text: l.to_string() })
+                }
+                continue;
+            }
+            if !listing {
+                return Err(FsError::OutputWithoutLs { line })
+            }
+            match l.split_once(' ') {
+                Some(("dir", name)) => { fs.mkdir(current, name, line)?; },
+                Some((size, name)) => {
+                    let size = size.parse().map_err(|_| FsError::BadListing { line, text: l.to_string() })?;
+                    fs.touch(current, name, size, line)?;
+                },
+                None => return Err(FsError::BadListing { line, text: l.to_string() })
+            }
+        }
+        fs.update_sizes();
+        Ok(fs)
+    }
+
+    fn children(&self, index: usize) -> Option<&BTreeMap<String, usize>> {
+        match &self.nodes[index].kind {
+            NodeKind::Dir(children) => Some(children),
+            NodeKind::File(_) => None
+        }
+    }
+
+    fn add_child(&mut self, parent: usize, name: &str, kind: NodeKind) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(Node { name: name.to_string(), parent: Some(parent), kind });
+        self.sizes.push(0);
+        if let NodeKind::Dir(children) = &mut self.nodes[parent].kind {
+            children.insert(name.to_string(), index);
+        }
+        index
+    }
+
+    fn mkdir(&mut self, parent: usize, name: &str, line: usize) -> Result<usize, FsError> {
+        match self.children(parent).and_then(|c| c.get(name)) {
+            Some(&index) if self.nodes[index].is_dir() => Ok(index),
+            Some(&index) => Err(FsError::NotADirectory { line, path: self.path(index) }),
+            None => Ok(self.add_child(parent, name, NodeKind::Dir(BTreeMap::new())))
+        }
+    }
+
+    fn touch(&mut self, parent: usize, name: &str, size: usize, line: usize) -> Result<usize, FsError> {
+        match self.children(parent).and_then(|c| c.get(name)) {
+            Some(&index) => match self.nodes[index].kind {
+                NodeKind::File(old) if old == size => Ok(index),
+                NodeKind::File(old) => Err(FsError::ConflictingSize { line, path: self.path(index), old, new: size }),
+                NodeKind::Dir(_) => Err(FsError::ConflictingKind { line, path: self.path(index) })
+            },
+            None => Ok(self.add_child(parent, name, NodeKind::File(size)))
+        }
+    }
+
+    fn update_sizes(&mut self) {
+        // children always come after their parents in the vector
+        // so a single pass from the back totals everything
+        for (i, node) in self.nodes.iter().enumerate() {
+            self.sizes[i] = match node.kind {
+                NodeKind::File(size) => size,
+                NodeKind::Dir(_) => 0
+            };
+        }
+        for i in (1..self.nodes.len()).rev() {
+            if let Some(p) = self.nodes[i].parent {
+                self.sizes[p] += self.sizes[i];
+            }
+        }
+    }
+
+    pub fn node(&self, index: usize) -> &Node {
+        &self.nodes[index]
+    }
+
+    /// The absolute path of a node, e.g. `/a/e/i`.
+    pub fn path(&self, index: usize) -> String {
+        let mut parts = vec![];
+        let mut i = index;
+        while let Some(p) = self.nodes[i].parent {
+            parts.push(self.nodes[i].name.as_str());
+            i = p;
+        }
+        parts.reverse();
+        format!("/{}", parts.join("/"))
+    }
+
+    /// Finds the node at an absolute path.
+    pub fn lookup(&self, path: &str) -> Option<usize> {
+        path.split('/')
+            .filter(|part| !part.is_empty())
+            .try_fold(ROOT, |i, part| self.children(i)?.get(part).copied())
+    }
+
+    /// Total size of a file or everything under a directory.
+    pub fn size(&self, index: usize) -> usize {
+        self.sizes[index]
+    }
+
+    /// Like `du`: the total size of the file or directory at `path`.
+    pub fn du(&self, path: &str) -> Option<usize> {
+        self.lookup(path).map(|i| self.sizes[i])
+    }
+
+    /// Every directory's path and total size, parents before children.
+    pub fn dirs(&self) -> impl Iterator<Item = (String, usize)> + '_ {
+        self.nodes.iter()
+            .enumerate()
+            .filter(|(_, n)| n.is_dir())
+            .map(|(i, _)| (self.path(i), self.sizes[i]))
+    }
+
+    /// Paths matching a glob such as `/a/*/*.txt` or `/**/d.?og`.
+    /// `*` and `?` stay within one path component, `**` spans any number
+    /// of them.
+    pub fn glob(&self, pattern: &str) -> Vec<String> {
+        let parts:Vec<&str> = pattern.split('/').filter(|p| !p.is_empty()).collect();
+        let mut found = vec![];
+        self.glob_from(ROOT, &parts, &mut found);
+        let mut paths:Vec<String> = found.into_iter().map(|i| self.path(i)).collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    fn glob_from(&self, index: usize, parts: &[&str], found: &mut Vec<usize>) {
+        let Some((first, rest)) = parts.split_first() else {
+            found.push(index);
+            return
+        };
+        let Some(children) = self.children(index) else { return };
+
+        if *first == "**" {
+            self.glob_from(index, rest, found);
+            for &child in children.values() {
+                self.glob_from(child, parts, found);
+            }
+        } else {
+            for (name, &child) in children {
+                if wildcard_match(first.as_bytes(), name.as_bytes()) {
+                    self.glob_from(child, rest, found);
+                }
+            }
+        }
+    }
+
+    /// The tree as JSON with every node's name, type and size.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(ROOT, &mut out);
+        out
+    }
+
+    fn write_json(&self, index: usize, out: &mut String) {
+        let node = &self.nodes[index];
+        out.push_str("{\"name\":");
+        push_json_string(&node.name, out);
+        match &node.kind {
+            NodeKind::File(size) => out.push_str(&format!(",\"type\":\"file\",\"size\":{}}}", size)),
+            NodeKind::Dir(children) => {
+                out.push_str(&format!(",\"type\":\"dir\",\"size\":{},\"children\":[", self.sizes[index]));
+                for (n, &child) in children.values().enumerate() {
+                    if n > 0 {
+                        out.push(',');
+                    }
+                    self.write_json(child, out);
+                }
+                out.push_str("]}");
+            }
+        }
+    }
+
+    fn fmt_node(&self, index: usize, depth: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        let node = &self.nodes[index];
+        let indent = "  ".repeat(depth);
+        match &node.kind {
+            NodeKind::File(size) => writeln!(f, "{}- {} (file, size={})", indent, node.name, size),
+            NodeKind::Dir(children) => {
+                writeln!(f, "{}- {} (dir)", indent, node.name)?;
+                children.values().try_for_each(|&child| self.fmt_node(child, depth + 1, f))
+            }
+        }
+    }
+}
+
+impl Default for VirtualFs {
+    fn default() -> Self {
+        VirtualFs::new()
+    }
+}
+
+impl FromStr for VirtualFs {
+    type Err = FsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VirtualFs::from_transcript(s)
+    }
+}
+
+// Renders the tree the way the puzzle text draws it.
+impl fmt::Display for VirtualFs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_node(ROOT, 0, f)
+    }
+}
+
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            wildcard_match(rest, name) || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        },
+        (Some((b'?', rest)), Some((_, name_rest))) => wildcard_match(rest, name_rest),
+        (Some((p, rest)), Some((c, name_rest))) => p == c && wildcard_match(rest, name_rest),
+        _ => false
+    }
+}
+
+fn push_json_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c)
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[test]
+    fn test_du() {
+        let fs:VirtualFs = EXAMPLE.parse().unwrap();
+        assert_eq!(fs.du("/"), Some(48381165));
+        assert_eq!(fs.du("/a"), Some(94853));
+        assert_eq!(fs.du("/a/e"), Some(584));
+        assert_eq!(fs.du("/d/k"), Some(7214296));
+        assert_eq!(fs.du("/nope"), None);
+    }
+
+    #[test]
+    fn test_listed_but_not_entered() {
+        let fs:VirtualFs = "$ cd /\n$ ls\ndir empty\n10 a".parse().unwrap();
+        assert_eq!(fs.du("/empty"), Some(0));
+        assert_eq!(fs.dirs().count(), 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = "$ cd /\n$ ls\n10 a\n$ ls\n11 a".parse::<VirtualFs>().unwrap_err();
+        assert_eq!(err, FsError::ConflictingSize { line: 5, path: "/a".to_string(), old: 10, new: 11 });
+
+        let err = "$ cd /\n$ cd ..".parse::<VirtualFs>().unwrap_err();
+        assert_eq!(err, FsError::AboveRoot { line: 2 });
+
+        let err = "$ ls\n10 a\n$ cd a".parse::<VirtualFs>().unwrap_err();
+        assert_eq!(err, FsError::NotADirectory { line: 3, path: "/a".to_string() });
+    }
+
+    #[test]
+    fn test_glob() {
+        let fs:VirtualFs = EXAMPLE.parse().unwrap();
+        assert_eq!(fs.glob("/*.txt"), vec!["/b.txt"]);
+        assert_eq!(fs.glob("/d/d.*"), vec!["/d/d.ext", "/d/d.log"]);
+        assert_eq!(fs.glob("/**/?"), vec!["/a", "/a/e", "/a/e/i", "/a/f", "/a/g", "/d", "/d/j", "/d/k"]);
+    }
+
+    #[test]
+    fn test_render() {
+        let fs:VirtualFs = "$ cd /\n$ ls\ndir a\n14 b.txt\n$ cd a\n$ ls\n29 f".parse().unwrap();
+        assert_eq!(fs.to_string(), "- / (dir)\n  - a (dir)\n    - f (file, size=29)\n  - b.txt (file, size=14)\n");
+        assert_eq!(
+            fs.to_json(),
+            r#"{"name":"/","type":"dir","size":43,"children":[{"name":"a","type":"dir","size":29,"children":[{"name":"f","type":"file","size":29}]},{"name":"b.txt","type":"file","size":14}]}"#
+        );
+    }
+}
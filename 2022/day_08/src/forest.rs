@@ -0,0 +1,273 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ForestError {
+    Empty,
+    Ragged { line: usize, expected: usize, found: usize },
+    BadTree { line: usize, column: usize, found: char },
+    // the trees don't fill the width and height given
+    Size { width: usize, height: usize, trees: usize }
+}
+
+impl fmt::Display for ForestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ForestError::Empty => write!(f, "there are no trees"),
+            ForestError::Ragged { line, expected, found } => {
+                write!(f, "line {}: expected {} trees but found {}", line, expected, found)
+            },
+            ForestError::BadTree { line, column, found } => {
+                write!(f, "line {}, column {}: '{}' isn't a tree height", line, column, found)
+            },
+            ForestError::Size { width, height, trees } => {
+                write!(f, "a {}x{} forest needs {} trees but there are {}", width, height, width * height, trees)
+            }
+        }
+    }
+}
+
+/// Which way a tree is looking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Left, Direction::Right, Direction::Up, Direction::Down];
+}
+
+/// Tree heights in rows, top row first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forest {
+    pub width: usize,
+    pub height: usize,
+    trees: Vec<u8>
+}
+
+/// What every tree can see in one direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Views {
+    pub direction: Direction,
+    // trees seen before one at least as tall, or the edge
+    pub distances: Vec<usize>,
+    // nothing as tall in the way, so it can be seen from that edge
+    pub clear: Vec<bool>
+}
+
+impl Forest {
+    pub fn new(width: usize, height: usize, trees: Vec<u8>) -> Result<Self, ForestError> {
+        if width == 0 || height == 0 {
+            return Err(ForestError::Empty)
+        }
+        if trees.len() != width * height {
+            return Err(ForestError::Size { width, height, trees: trees.len() })
+        }
+        Ok(Forest { width, height, trees })
+    }
+
+    pub fn tree(&self, x: usize, y: usize) -> u8 {
+        self.trees[y * self.width + x]
+    }
+
+    ///
+    /// Walks each line of trees starting from the edge `direction` looks
+    /// towards, keeping a stack of the trees that nothing since has hidden.
+    /// They get taller going down the stack, so the first one left after
+    /// popping the shorter ones is the tree that blocks the view.
+    ///
+    pub fn views(&self, direction: Direction) -> Views {
+        let (w, h) = (self.width, self.height);
+        // the cell at step k along line i
+        let cell = |i: usize, k: usize| match direction {
+            Direction::Left => i * w + k,
+            Direction::Right => i * w + w - 1 - k,
+            Direction::Up => k * w + i,
+            Direction::Down => (h - 1 - k) * w + i
+        };
+        let (lines, length) = match direction {
+            Direction::Left | Direction::Right => (h, w),
+            Direction::Up | Direction::Down => (w, h)
+        };
+        let mut distances = vec![0; w * h];
+        let mut clear = vec![false; w * h];
+        let mut stack:Vec<usize> = Vec::with_capacity(length);
+
+        for i in 0..lines {
+            stack.clear();
+            for k in 0..length {
+                let tree = self.trees[cell(i, k)];
+                while stack.last().is_some_and(|&j| self.trees[cell(i, j)] < tree) {
+                    stack.pop();
+                }
+                match stack.last() {
+                    Some(&j) => distances[cell(i, k)] = k - j,
+                    None => {
+                        distances[cell(i, k)] = k;
+                        clear[cell(i, k)] = true;
+                    }
+                }
+                stack.push(k);
+            }
+        }
+        Views { direction, distances, clear }
+    }
+
+    /// Views in every direction, in the order of `Direction::ALL`.
+    pub fn survey(&self) -> Survey {
+        Survey { width: self.width, height: self.height, views: Direction::ALL.map(|d| self.views(d)) }
+    }
+}
+
+impl FromStr for Forest {
+    type Err = ForestError;
+
+    /// One digit per tree, one row per line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut trees = vec![];
+        for (i, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let line = line.trim();
+            for (column, c) in line.chars().enumerate() {
+                let tree = c.to_digit(10).ok_or(ForestError::BadTree { line: i + 1, column: column + 1, found: c })?;
+                trees.push(tree as u8);
+            }
+            let expected = *width.get_or_insert(line.len());
+            if line.len() != expected {
+                return Err(ForestError::Ragged { line: i + 1, expected, found: line.len() })
+            }
+        }
+        let width = width.ok_or(ForestError::Empty)?;
+        Forest::new(width, trees.len() / width, trees)
+    }
+}
+
+/// The views from every tree in all four directions.
+#[derive(Debug, Clone)]
+pub struct Survey {
+    pub width: usize,
+    pub height: usize,
+    pub views: [Views; 4]
+}
+
+impl Survey {
+    /// How far each tree can see in one direction, a row at a time from the top.
+    pub fn distances(&self, direction: Direction) -> Vec<Vec<usize>> {
+        let view = self.views.iter().find(|v| v.direction == direction).unwrap();
+        view.distances.chunks(self.width).map(|row| row.to_vec()).collect()
+    }
+
+    /// How many edges the tree can be seen from.
+    pub fn visibility(&self, x: usize, y: usize) -> usize {
+        self.views.iter().filter(|v| v.clear[y * self.width + x]).count()
+    }
+
+    pub fn visible_count(&self) -> usize {
+        (0..self.width * self.height)
+            .filter(|&i| self.views.iter().any(|v| v.clear[i]))
+            .count()
+    }
+
+    /// The four viewing distances multiplied together.
+    pub fn scenic_score(&self, x: usize, y: usize) -> usize {
+        self.views.iter().map(|v| v.distances[y * self.width + x]).product()
+    }
+
+    /// The best scenic score and the first tree, reading row by row, that has it.
+    pub fn best_scenic(&self) -> (usize, (usize, usize)) {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| (self.scenic_score(x, y), (x, y)))
+            .fold((0, (0, 0)), |best, s| if s.0 > best.0 { s } else { best })
+    }
+
+    ///
+    /// A map of how visible each tree is: `.` for hidden trees, then `-`, `+`,
+    /// `*` and `#` for trees seen from one, two, three or all four edges.
+    ///
+    pub fn heatmap(&self) -> String {
+        let shades = ['.', '-', '+', '*', '#'];
+        (0..self.height)
+            .map(|y| (0..self.width).map(|x| shades[self.visibility(x, y)]).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "30373\n25512\n65332\n33549\n35390";
+
+    #[test]
+    fn test_example() {
+        let forest:Forest = EXAMPLE.parse().unwrap();
+        let survey = forest.survey();
+        assert_eq!(survey.visible_count(), 21);
+        assert_eq!(survey.best_scenic(), (8, (2, 3)));
+
+        // the middle 5 in the second row
+        let seen:Vec<usize> = Direction::ALL.iter().map(|&d| survey.distances(d)[1][2]).collect();
+        assert_eq!(seen, vec![1, 2, 1, 2]);
+        assert_eq!(survey.distances(Direction::Left)[..2], [vec![0, 1, 2, 3, 1], vec![0, 1, 1, 1, 2]]);
+        assert_eq!(survey.scenic_score(2, 1), 4);
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let survey = EXAMPLE.parse::<Forest>().unwrap().survey();
+        assert_eq!(survey.heatmap(), "+--*+\n-++.-\n#-.--\n-.+.#\n++-#+");
+        assert_eq!("7".parse::<Forest>().unwrap().survey().heatmap(), "#");
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let forests = [
+            // one tree, one row, one column
+            "5", "3034919", "3\n0\n3\n4\n9",
+            // all the same height, so every view stops at the next tree
+            "444\n444\n444",
+            // stairs up and down, with zeros and nines at the edges
+            "01234\n12345\n98765\n00000\n99999",
+            // a tall tree hidden by equal neighbours, and a dip in a plateau
+            "77777\n78887\n78987\n78887\n71117",
+        ];
+        for text in forests {
+            let forest:Forest = text.parse().unwrap();
+            let (w, h) = (forest.width, forest.height);
+            let survey = forest.survey();
+            for y in 0..h {
+                for x in 0..w {
+                    let tree = forest.tree(x, y);
+                    let look = |path: Vec<(usize, usize)>| {
+                        let blocked = path.iter().position(|&(a, b)| forest.tree(a, b) >= tree);
+                        (blocked.map_or(path.len(), |p| p + 1), blocked.is_none())
+                    };
+                    let paths = [
+                        (0..x).rev().map(|a| (a, y)).collect(),
+                        (x + 1..w).map(|a| (a, y)).collect(),
+                        (0..y).rev().map(|b| (x, b)).collect(),
+                        (y + 1..h).map(|b| (x, b)).collect()
+                    ];
+                    for (view, path) in survey.views.iter().zip(paths) {
+                        let (distance, clear) = look(path);
+                        assert_eq!(view.distances[y * w + x], distance);
+                        assert_eq!(view.clear[y * w + x], clear);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bad_input() {
+        assert_eq!("123\n12".parse::<Forest>().unwrap_err(), ForestError::Ragged { line: 2, expected: 3, found: 2 });
+        assert_eq!("12x".parse::<Forest>().unwrap_err(), ForestError::BadTree { line: 1, column: 3, found: 'x' });
+        assert_eq!("\n".parse::<Forest>().unwrap_err(), ForestError::Empty);
+        assert_eq!(Forest::new(2, 2, vec![1, 2, 3]).unwrap_err(), ForestError::Size { width: 2, height: 2, trees: 3 });
+    }
+}
@@ -0,0 +1,4 @@
+extern crate num;
+
+pub mod point;
+pub mod rope;
@@ -1,7 +1,7 @@
 use num::clamp;
 use std::ops;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Point {
     pub x: i32,
     pub y: i32
@@ -61,15 +61,15 @@ mod tests {
     fn test_touching_false() {
         let p1 = Point::new();
         let p2 = Point{x: 2, y:0};
-        assert_eq!(p1.touching(&p2), false);
+        assert!(!p1.touching(&p2));
 
         let p1 = Point::new();
         let p2 = Point{x: 1, y:2};
-        assert_eq!(p1.touching(&p2), false);
+        assert!(!p1.touching(&p2));
 
         let p1 = Point::new();
         let p2 = Point{x: -2, y:1};
-        assert_eq!(p1.touching(&p2), false);
+        assert!(!p1.touching(&p2));
     }
 
     #[test]
@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::fmt;
+use crate::point::Point;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RopeError {
+    NoKnots,
+    BadLine { line: usize, text: String },
+    BadDirection { line: usize, text: String },
+    BadCount { line: usize, text: String }
+}
+
+impl fmt::Display for RopeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RopeError::NoKnots => write!(f, "a rope needs at least one knot"),
+            RopeError::BadLine { line, text } => write!(f, "line {}: expected a direction and a count in '{}'", line, text),
+            RopeError::BadDirection { line, text } => write!(f, "line {}: '{}' isn't a direction", line, text),
+            RopeError::BadCount { line, text } => write!(f, "line {}: '{}' isn't a count", line, text)
+        }
+    }
+}
+
+/// The head moving `count` steps one way. Up is towards smaller y.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Motion {
+    pub line: usize,
+    pub direction: Point,
+    pub count: u32
+}
+
+fn direction(s: &str) -> Option<Point> {
+    let (x, y) = match s {
+        "U" => (0, -1),
+        "D" => (0, 1),
+        "L" => (-1, 0),
+        "R" => (1, 0),
+        "UL" => (-1, -1),
+        "UR" => (1, -1),
+        "DL" => (-1, 1),
+        "DR" => (1, 1),
+        _ => return None
+    };
+    Some(Point { x, y })
+}
+
+/// One motion per line: U, D, L or R, or a diagonal like UR, then a count.
+pub fn parse_motions(s: &str) -> Result<Vec<Motion>, RopeError> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            let line = i + 1;
+            let (d, n) = l.trim().split_once(' ').ok_or_else(|| RopeError::BadLine { line, text: l.to_string() })?;
+            Ok(Motion {
+                line,
+                direction: direction(d).ok_or_else(|| RopeError::BadDirection { line, text: d.to_string() })?,
+                count: n.trim().parse().map_err(|_| RopeError::BadCount { line, text: n.to_string() })?
+            })
+        })
+        .collect()
+}
+
+///
+/// A rope of knots in one flat array, head first, all starting at the origin,
+/// with the places each knot has been.
+///
+#[derive(Debug, Clone)]
+pub struct Rope {
+    pub knots: Vec<Point>,
+    visited: Vec<HashSet<Point>>
+}
+
+impl Rope {
+    pub fn new(knots: usize) -> Result<Self, RopeError> {
+        if knots == 0 {
+            return Err(RopeError::NoKnots)
+        }
+        Ok(Rope {
+            knots: vec![Point::new(); knots],
+            visited: vec![HashSet::from([Point::new()]); knots]
+        })
+    }
+
+    /// Moves the head one step and pulls the rest along behind it.
+    pub fn step(&mut self, direction: Point) {
+        self.knots[0] = &self.knots[0] + &direction;
+        self.visited[0].insert(self.knots[0]);
+        for i in 1..self.knots.len() {
+            let (leader, knot) = (self.knots[i - 1], self.knots[i]);
+            // once a knot stays put so does everything behind it
+            if knot.touching(&leader) {
+                break
+            }
+            self.knots[i] = &knot + &knot.direction(&leader);
+            self.visited[i].insert(self.knots[i]);
+        }
+    }
+
+    /// Makes every motion, calling `observe` after each single step.
+    pub fn run(&mut self, motions: &[Motion], mut observe: impl FnMut(&Rope)) {
+        for m in motions {
+            for _ in 0..m.count {
+                self.step(m.direction);
+                observe(self);
+            }
+        }
+    }
+
+    /// How many places knot `knot` has been, 0 being the head.
+    pub fn visited(&self, knot: usize) -> usize {
+        self.visited[knot].len()
+    }
+
+    pub fn tail_visited(&self) -> usize {
+        self.visited(self.knots.len() - 1)
+    }
+
+    ///
+    /// Draws the rope the way the puzzle does: `H` for the head, then the knots
+    /// by number (letters after 9), `s` for the start and `#` where the tail has
+    /// been. Front knots cover the ones behind. Big enough for everything.
+    ///
+    pub fn render(&self) -> String {
+        let tail = self.visited.last().unwrap();
+        let everything = || self.knots.iter().chain(tail);
+        let (min_x, max_x) = (everything().map(|p| p.x).min().unwrap(), everything().map(|p| p.x).max().unwrap());
+        let (min_y, max_y) = (everything().map(|p| p.y).min().unwrap(), everything().map(|p| p.y).max().unwrap());
+
+        (min_y..=max_y)
+            .map(|y| (min_x..=max_x)
+                .map(|x| {
+                    let p = Point { x, y };
+                    match self.knots.iter().position(|&k| k == p) {
+                        Some(0) => 'H',
+                        Some(i) => char::from_digit(i as u32 % 36, 36).unwrap(),
+                        None if p == Point::new() => 's',
+                        None if tail.contains(&p) => '#',
+                        None => '.'
+                    }
+                })
+                .collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2";
+    static LARGER: &str = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20";
+
+    fn tail_visited(input: &str, knots: usize) -> usize {
+        let mut rope = Rope::new(knots).unwrap();
+        rope.run(&parse_motions(input).unwrap(), |_| {});
+        rope.tail_visited()
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(tail_visited(EXAMPLE, 2), 13);
+        assert_eq!(tail_visited(EXAMPLE, 10), 1);
+        assert_eq!(tail_visited(LARGER, 10), 36);
+    }
+
+    #[test]
+    fn test_step() {
+        let mut head = Rope::new(1).unwrap();
+        head.step(Point { x: 1, y: 0 });
+        assert_eq!(head.knots[0], Point { x: 1, y: 0 });
+        head.step(Point { x: 1, y: -1 });
+        assert_eq!(head.knots[0], Point { x: 2, y: -1 });
+        assert_eq!(head.tail_visited(), 3);
+    }
+
+    #[test]
+    fn test_every_knot() {
+        // knots never look behind them, so knot k goes where a k+1 knot rope's tail does
+        let mut rope = Rope::new(10).unwrap();
+        rope.run(&parse_motions(LARGER).unwrap(), |_| {});
+        for k in 1..10 {
+            assert_eq!(rope.visited(k), tail_visited(LARGER, k + 1));
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut rope = Rope::new(3).unwrap();
+        let mut frames = vec![];
+        rope.run(&parse_motions("R 3\nUR 1").unwrap(), |r| frames.push(r.render()));
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0], "1H");
+        assert_eq!(frames[2], "s21H");
+        assert_eq!(frames[3], "..21H\ns#...");
+        assert_eq!(rope.knots, vec![Point { x: 4, y: -1 }, Point { x: 3, y: -1 }, Point { x: 2, y: -1 }]);
+    }
+
+    #[test]
+    fn test_bad_input() {
+        assert_eq!(parse_motions("R 1\n\nX 2").unwrap_err(), RopeError::BadDirection { line: 3, text: "X".to_string() });
+        assert_eq!(parse_motions("R two").unwrap_err(), RopeError::BadCount { line: 1, text: "two".to_string() });
+        assert_eq!(parse_motions("U 1\nR").unwrap_err(), RopeError::BadLine { line: 2, text: "R".to_string() });
+        assert!(matches!(Rope::new(0), Err(RopeError::NoKnots)));
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+use thirteen::ocr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CpuError {
+    UnknownOpcode { line: usize, text: String },
+    BadArgument { line: usize, text: String },
+    EmptyScreen { width: usize, height: usize }
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CpuError::UnknownOpcode { line, text } => write!(f, "line {}: '{}' isn't an instruction", line, text),
+            CpuError::BadArgument { line, text } => write!(f, "line {}: bad argument in '{}'", line, text),
+            CpuError::EmptyScreen { width, height } => write!(f, "a {}x{} screen has no pixels", width, height)
+        }
+    }
+}
+
+///
+/// What the handheld understands. A new opcode needs a variant here,
+/// its cost in `cycles`, what it does in `execute` and a name in `parse_program`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    Addx(i64)
+}
+
+impl Instruction {
+    /// How many cycles it takes. Its effect lands at the end of the last one.
+    pub fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2
+        }
+    }
+
+    fn execute(&self, x: &mut i64) {
+        match self {
+            Instruction::Noop => {},
+            Instruction::Addx(v) => *x += v
+        }
+    }
+}
+
+/// One instruction per line.
+pub fn parse_program(s: &str) -> Result<Vec<Instruction>, CpuError> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            let line = i + 1;
+            let words:Vec<&str> = l.split_whitespace().collect();
+            let argument = |w: &str| w.parse().map_err(|_| CpuError::BadArgument { line, text: l.to_string() });
+            match words[..] {
+                ["noop"] => Ok(Instruction::Noop),
+                ["addx", v] => Ok(Instruction::Addx(argument(v)?)),
+                ["noop", ..] | ["addx", ..] => Err(CpuError::BadArgument { line, text: l.to_string() }),
+                _ => Err(CpuError::UnknownOpcode { line, text: l.to_string() })
+            }
+        })
+        .collect()
+}
+
+/// The handheld's processor: one register and a cycle counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cpu {
+    pub x: i64,
+    pub cycle: usize
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Cpu { x: 1, cycle: 0 }
+    }
+}
+
+impl Cpu {
+    ///
+    /// Runs the program, calling `during` once for every cycle with the cycle
+    /// number, counting from 1, and the value of x while that cycle happens.
+    ///
+    pub fn run(&mut self, program: &[Instruction], mut during: impl FnMut(usize, i64)) {
+        for instruction in program {
+            for _ in 0..instruction.cycles() {
+                self.cycle += 1;
+                during(self.cycle, self.x);
+            }
+            instruction.execute(&mut self.x);
+        }
+    }
+}
+
+/// The sum of cycle times x during each of the `probes` cycles.
+pub fn signal_strength(program: &[Instruction], probes: &[usize]) -> i64 {
+    let mut total = 0;
+    Cpu::default().run(program, |cycle, x| {
+        if probes.contains(&cycle) {
+            total += cycle as i64 * x;
+        }
+    });
+    total
+}
+
+///
+/// The screen, drawn one pixel a cycle, row by row, wrapping to the top
+/// when it's full. A pixel is lit when the three wide sprite centred on x
+/// covers it.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crt {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<bool>
+}
+
+impl Default for Crt {
+    /// The handheld's own 40x6 screen.
+    fn default() -> Self {
+        Crt { width: 40, height: 6, pixels: vec![false; 240] }
+    }
+}
+
+impl Crt {
+    pub fn new(width: usize, height: usize) -> Result<Self, CpuError> {
+        if width == 0 || height == 0 {
+            return Err(CpuError::EmptyScreen { width, height })
+        }
+        Ok(Crt { width, height, pixels: vec![false; width * height] })
+    }
+
+    /// Draws pixel number `pixel`, counting from 0 along the rows and
+    /// wrapping back to the top, lit if the sprite at `x` covers it.
+    pub fn draw(&mut self, pixel: usize, x: i64) {
+        let position = pixel % self.pixels.len();
+        let column = (position % self.width) as i64;
+        self.pixels[position] = (column - x).abs() <= 1;
+    }
+
+    pub fn lit(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.pixels[y * self.width + x]
+    }
+
+    /// Runs the program on a fresh CPU, drawing every cycle.
+    pub fn show(&mut self, program: &[Instruction]) {
+        Cpu::default().run(program, |cycle, x| self.draw(cycle - 1, x));
+    }
+
+    pub fn render(&self) -> String {
+        self.pixels.chunks(self.width)
+            .map(|row| row.iter().map(|&p| if p { '#' } else { '.' }).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// The letters on the top rows of the screen, if they can all be read.
+    pub fn read(&self) -> Option<String> {
+        ocr::read(self.width, |x, y| self.lit(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_program() {
+        let program = parse_program("noop\naddx 3\naddx -5").unwrap();
+        let mut cpu = Cpu::default();
+        let mut during = vec![];
+        cpu.run(&program, |cycle, x| during.push((cycle, x)));
+        assert_eq!(during, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+        assert_eq!(cpu, Cpu { x: -1, cycle: 5 });
+    }
+
+    #[test]
+    fn test_against_ticks() {
+        // 17 cycles, so additions land either side of every probe, with x
+        // going off both edges of the screen and an addx of 0
+        let block = "addx 15\naddx -11\nnoop\naddx 0\naddx -6\naddx 40\naddx -41\nnoop\nnoop\naddx 3\n";
+        let program = parse_program(&block.repeat(15)).unwrap();
+
+        // x at the start of every cycle, the way part one was first worked out
+        let mut ticks = vec![1];
+        let mut x = 1;
+        for i in &program {
+            ticks.push(x);
+            if let Instruction::Addx(v) = i {
+                x += v;
+                ticks.push(x);
+            }
+        }
+        let probes:Vec<usize> = (20..=220).step_by(40).collect();
+        let expected:i64 = probes.iter().map(|&c| c as i64 * ticks[c - 1]).sum();
+        assert_eq!(signal_strength(&program, &probes), expected);
+
+        // only the first frame, the program runs longer than that
+        let mut crt = Crt::new(40, 6).unwrap();
+        Cpu::default().run(&program, |cycle, x| if cycle <= 240 { crt.draw(cycle - 1, x) });
+        for (pos, x) in ticks.iter().take(240).enumerate() {
+            assert_eq!(crt.lit(pos % 40, pos / 40), ((pos % 40) as i64 - x).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_read_screen() {
+        let drawing = [
+            "#..#.####.###..",
+            "#..#.#....#..#.",
+            "####.###..###..",
+            "#..#.#....#..#.",
+            "#..#.#....#..#.",
+            "#..#.####.###..",
+        ];
+        let mut crt = Crt::new(15, 6).unwrap();
+        for pixel in 0..90 {
+            let (column, row) = (pixel % 15, pixel / 15);
+            // park the sprite on the pixel or well away from it
+            let lit = drawing[row].as_bytes()[column] == b'#';
+            crt.draw(pixel, if lit { column as i64 } else { -5 });
+        }
+        assert_eq!(crt.render(), drawing.join("\n"));
+        assert_eq!(crt.read(), Some("HEB".to_string()));
+        assert_eq!(Crt::new(15, 6).unwrap().read(), None);
+        assert_eq!(Crt::new(0, 6), Err(CpuError::EmptyScreen { width: 0, height: 6 }));
+        assert_eq!(Crt::default(), Crt::new(40, 6).unwrap());
+    }
+
+    #[test]
+    fn test_bad_program() {
+        assert_eq!(parse_program("noop\njmp 3").unwrap_err(), CpuError::UnknownOpcode { line: 2, text: "jmp 3".to_string() });
+        assert_eq!(parse_program("addx x").unwrap_err(), CpuError::BadArgument { line: 1, text: "addx x".to_string() });
+        assert_eq!(parse_program("\naddx").unwrap_err(), CpuError::BadArgument { line: 2, text: "addx".to_string() });
+    }
+}
@@ -1,49 +1,20 @@
-use std::fs::File;
-use std::path::Path;
-use std::io::{BufReader, BufRead};
+use std::fs;
+use day_10::cpu::{parse_program, signal_strength, Crt};
 
 static DATA: &str  = "data.txt";
 
 
 fn main() {
-    let p = Path::new(DATA);
-    let f = File::open(p).expect("could not open the file!");
-    let it = BufReader::new(f).lines().map(|line| line.unwrap());
+    let input = fs::read_to_string(DATA).expect("could not open the file!");
+    let program = parse_program(&input).unwrap_or_else(|e| panic!("{}", e));
 
-    let mut ticks = vec![1];
-    let mut x:i32 = 1;
+    let probes:Vec<usize> = (20..=220).step_by(40).collect();
+    println!("Part one: {}", signal_strength(&program, &probes));
 
-    for line in it {
-        match line.split_once(" ") {
-            Some((_, n)) => {
-                ticks.push(x);
-                x += n.parse::<i32>().expect("Somthing went terribly wrong.");
-                ticks.push(x);
-            },
-            None => {
-                ticks.push(x)
-            }
-        }
+    let mut crt = Crt::default();
+    crt.show(&program);
+    match crt.read() {
+        Some(text) => println!("Part two: {}", text),
+        None => println!("Part two: \n{}", crt.render())
     }
-
-    let total:i32 = (20..=220)
-        .step_by(40)
-        .fold(0, |acc, i| acc + i as i32 * ticks[i-1]); 
-      
-    println!("Part one: {}", total);
-    
-    println!("Part two: ");
-    for row_start in (0..=200).step_by(40) {
-        let v:String = (0..40)
-            .map(|i| {
-                match (i as i32 - ticks[i + row_start]).abs() <= 1 {
-                    true => '#',
-                    false => '.'
-                }
-            })
-            .collect();
-        println!("{}", v);
-    }
-
 }
-
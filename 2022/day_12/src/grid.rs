@@ -1,29 +1,169 @@
+use std::collections::VecDeque;
+
+// p = (row, col)
+pub type Point = (usize, usize);
+
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (0, -1), (1, 0), (0, 1)];
+
+#[derive(Debug, Clone, Copy)]
 pub struct Grid {
-    height: usize,
-    width: usize,
+    pub height: usize,
+    pub width: usize,
 }
 
 impl Grid {
-    // p = (row, col)
     pub fn new(h:usize, w: usize) -> Grid {
         Grid {height: h, width: w }
     }
-    pub fn neighbors(&self, p: (usize, usize)) -> Vec<(usize, usize)> {
-        //println!("Getting neighbors of {:?}", p);
-        let mut v = Vec::with_capacity(4);
-        if p.0 > 0 {
-            v.push((p.0 - 1, p.1));
+
+    pub fn len(&self) -> usize {
+        self.height * self.width
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn index(&self, p: Point) -> usize {
+        p.0 * self.width + p.1
+    }
+
+    pub fn point(&self, i: usize) -> Point {
+        (i / self.width, i % self.width)
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let w = self.width;
+        (0..self.len()).map(move |i| (i / w, i % w))
+    }
+
+    pub fn neighbors(&self, p: (usize, usize)) -> impl Iterator<Item = Point> {
+        let (h, w) = (self.height, self.width);
+        DIRECTIONS.into_iter().filter_map(move |(dr, dc)| {
+            let row = p.0.checked_add_signed(dr).filter(|r| *r < h)?;
+            let col = p.1.checked_add_signed(dc).filter(|c| *c < w)?;
+            Some((row, col))
+        })
+    }
+
+    /// Breadth first search out from every source at once until the whole
+    /// reachable grid has a distance. `edge(from, to)` should return true
+    /// when it's possible to step from `from` to the neighbor `to`.
+    pub fn bfs<E>(&self, sources: &[Point], edge: E) -> Search
+    where E: Fn(Point, Point) -> bool {
+        self.bfs_until(sources, edge, |_| false).0
+    }
+
+    /// Like `bfs`, but stops at the first point where `target` is true
+    /// and returns it along with the search so far.
+    pub fn bfs_to<E, T>(&self, sources: &[Point], edge: E, target: T) -> Option<(Point, Search)>
+    where E: Fn(Point, Point) -> bool, T: Fn(Point) -> bool {
+        match self.bfs_until(sources, edge, target) {
+            (search, Some(found)) => Some((found, search)),
+            (_, None) => None
         }
-        if p.1 > 0 {
-            v.push((p.0, p.1 -1));
+    }
+
+    fn bfs_until<E, T>(&self, sources: &[Point], edge: E, target: T) -> (Search, Option<Point>)
+    where E: Fn(Point, Point) -> bool, T: Fn(Point) -> bool {
+        let mut search = Search {
+            grid: *self,
+            distances: vec![None; self.len()],
+            previous: vec![None; self.len()]
+        };
+        let mut q = VecDeque::new();
+
+        for &s in sources {
+            let i = self.index(s);
+            if search.distances[i].is_none() {
+                search.distances[i] = Some(0);
+                q.push_back(s);
+            }
         }
-        if p.0 + 1 < self.height {
-            v.push((p.0 + 1, p.1));
+
+        while let Some(current) = q.pop_front() {
+            if target(current) {
+                return (search, Some(current))
+            }
+            let steps = search.distances[self.index(current)].unwrap_or(0);
+            for p in self.neighbors(current) {
+                let i = self.index(p);
+                if search.distances[i].is_none() && edge(current, p) {
+                    search.distances[i] = Some(steps + 1);
+                    search.previous[i] = Some(current);
+                    q.push_back(p);
+                }
+            }
         }
-        if p.1 + 1 < self.width {
-            v.push((p.0, p.1 + 1));
+        (search, None)
+    }
+}
+
+/// The result of a breadth first search: a distance field over the grid and
+/// the predecessor of each point on a shortest route back to a source.
+#[derive(Debug, Clone)]
+pub struct Search {
+    grid: Grid,
+    distances: Vec<Option<usize>>,
+    previous: Vec<Option<Point>>
+}
+
+impl Search {
+    pub fn distance(&self, p: Point) -> Option<usize> {
+        self.distances[self.grid.index(p)]
+    }
+
+    pub fn previous(&self, p: Point) -> Option<Point> {
+        self.previous[self.grid.index(p)]
+    }
+
+    /// Distances for every cell, row by row. Unreachable cells are None.
+    pub fn distance_field(&self) -> Vec<Vec<Option<usize>>> {
+        self.distances.chunks(self.grid.width.max(1)).map(|row| row.to_vec()).collect()
+    }
+
+    /// The route from the nearest source to `p`, including both ends.
+    pub fn path_to(&self, p: Point) -> Option<Vec<Point>> {
+        self.distance(p)?;
+        let mut path = vec![p];
+        let mut current = p;
+        while let Some(prev) = self.previous(current) {
+            path.push(prev);
+            current = prev;
         }
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        v
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(2, 3);
+        let n:Vec<Point> = grid.neighbors((0, 0)).collect();
+        assert_eq!(n, vec![(1, 0), (0, 1)]);
+        let n:Vec<Point> = grid.neighbors((1, 2)).collect();
+        assert_eq!(n, vec![(0, 2), (1, 1)]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_multi_source() {
+        let grid = Grid::new(1, 5);
+        let search = grid.bfs(&[(0, 0), (0, 4)], |_, _| true);
+        let field:Vec<Option<usize>> = search.distance_field().concat();
+        assert_eq!(field, vec![Some(0), Some(1), Some(2), Some(1), Some(0)]);
+        assert_eq!(search.path_to((0, 3)), Some(vec![(0, 4), (0, 3)]));
+    }
+
+    #[test]
+    fn test_blocked() {
+        let grid = Grid::new(1, 3);
+        let search = grid.bfs(&[(0, 0)], |_, to| to != (0, 1));
+        assert_eq!(search.distance((0, 2)), None);
+        assert_eq!(search.path_to((0, 2)), None);
+        assert!(grid.bfs_to(&[(0, 0)], |_, to| to != (0, 1), |p| p == (0, 2)).is_none());
+    }
+}
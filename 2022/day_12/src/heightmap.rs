@@ -0,0 +1,146 @@
+use std::str::FromStr;
+use crate::grid::{Grid, Point, Search};
+
+pub type Map = Vec<Vec<u8>>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseMapError {
+    Empty,
+    Ragged(usize),
+    BadHeight(usize, usize),
+    MissingStart,
+    MissingEnd
+}
+
+#[derive(Debug)]
+pub struct HeightMap {
+    pub heights: Map,
+    pub grid: Grid,
+    pub start: Point,
+    pub end: Point
+}
+
+impl FromStr for HeightMap {
+    type Err = ParseMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut start = None;
+        let mut end = None;
+        let mut heights = Map::new();
+
+        for (row, line) in s.lines().enumerate() {
+            let r = line.bytes().enumerate().map(|(col, c)| {
+                match c {
+                    b'S' => {
+                        start = Some((row, col));
+                        Ok(b'a')
+                    },
+                    b'E' => {
+                        end = Some((row, col));
+                        Ok(b'z')
+                    },
+                    b'a'..=b'z' => Ok(c),
+                    _ => Err(ParseMapError::BadHeight(row, col))
+                }
+            }).collect::<Result<Vec<u8>, _>>()?;
+
+            if heights.first().is_some_and(|first| first.len() != r.len()) {
+                return Err(ParseMapError::Ragged(row))
+            }
+            heights.push(r);
+        }
+        let width = heights.first().map(|r| r.len()).filter(|w| *w > 0).ok_or(ParseMapError::Empty)?;
+
+        Ok(HeightMap {
+            grid: Grid::new(heights.len(), width),
+            heights,
+            start: start.ok_or(ParseMapError::MissingStart)?,
+            end: end.ok_or(ParseMapError::MissingEnd)?
+        })
+    }
+}
+
+impl HeightMap {
+    pub fn height(&self, p: Point) -> u8 {
+        self.heights[p.0][p.1]
+    }
+
+    /// You can climb at most one step up but drop any distance.
+    pub fn climbable(&self, from: Point, to: Point) -> bool {
+        self.height(to) <= self.height(from) + 1
+    }
+
+    pub fn points_at(&self, height: u8) -> Vec<Point> {
+        self.grid.points().filter(|p| self.height(*p) == height).collect()
+    }
+
+    /// Distances from every source to every reachable square.
+    pub fn distances_from(&self, sources: &[Point]) -> Search {
+        self.grid.bfs(sources, |from, to| self.climbable(from, to))
+    }
+
+    /// The shortest route from any of the sources to the end.
+    pub fn route_from(&self, sources: &[Point]) -> Option<Vec<Point>> {
+        let (end, search) = self.grid.bfs_to(sources, |from, to| self.climbable(from, to), |p| p == self.end)?;
+        search.path_to(end)
+    }
+
+    /// Draws a route the way the puzzle does: each step is marked with
+    /// the direction it leaves in, the end with `E` and the rest with `.`
+    pub fn render_route(&self, route: &[Point]) -> String {
+        let mut canvas = vec![vec!['.'; self.grid.width]; self.grid.height];
+
+        for step in route.windows(2) {
+            let (from, to) = (step[0], step[1]);
+            canvas[from.0][from.1] = match (to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize) {
+                (-1, 0) => '^',
+                (1, 0) => 'v',
+                (0, -1) => '<',
+                _ => '>'
+            };
+        }
+        if let Some(last) = route.last() {
+            canvas[last.0][last.1] = 'E';
+        }
+        canvas.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+    #[test]
+    fn test_route() {
+        let map:HeightMap = EXAMPLE.parse().unwrap();
+        let route = map.route_from(&[map.start]).unwrap();
+        assert_eq!(route.len() - 1, 31);
+        assert_eq!(map.render_route(&route), "v..v<<<<
+>v.vv<<^
+.v.v>E^^
+.>v>>>^^
+..>>>>>^");
+    }
+
+    #[test]
+    fn test_multi_source() {
+        let map:HeightMap = EXAMPLE.parse().unwrap();
+        let route = map.route_from(&map.points_at(b'a')).unwrap();
+        assert_eq!(route.len() - 1, 29);
+        let field = map.distances_from(&[map.start]);
+        assert_eq!(field.distance(map.end), Some(31));
+    }
+
+    #[test]
+    fn test_bad_map() {
+        assert_eq!("Sab\nabcE".parse::<HeightMap>().unwrap_err(), ParseMapError::Ragged(1));
+        assert_eq!("Sab\nab1".parse::<HeightMap>().unwrap_err(), ParseMapError::BadHeight(1, 2));
+        assert_eq!("Sab\nabc".parse::<HeightMap>().unwrap_err(), ParseMapError::MissingEnd);
+    }
+}
@@ -0,0 +1,39 @@
+//! Benchmarks
+#[macro_use]
+extern crate bencher;
+extern crate day_20;
+use self::bencher::Bencher;
+
+use day_20::{linked, mixer};
+
+// puzzle sized input without needing data.txt
+fn make_data() -> Vec<isize> {
+    (0..5000).map(|i: isize| (i * 7919) % 20000 - 10000).collect()
+}
+
+fn bench_linked(b: &mut Bencher) {
+    let data = make_data();
+
+    b.iter(|| {
+        let mut l = linked::build_input(&data, 811589153);
+        (0..l.len()).for_each(|i| linked::shift(&mut l, i));
+        linked::reorder(&l)
+    })
+}
+
+fn bench_treap(b: &mut Bencher) {
+    let data = make_data();
+
+    b.iter(|| {
+        let mut m = mixer::build_input(&data, 811589153);
+        m.mix();
+        mixer::reorder(&m)
+    })
+}
+
+benchmark_group!(
+    benches,
+    bench_linked,
+    bench_treap
+);
+benchmark_main!(benches);
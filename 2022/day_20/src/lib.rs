@@ -0,0 +1,3 @@
+pub mod linked;
+pub mod mixer;
+
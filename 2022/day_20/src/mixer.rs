@@ -0,0 +1,250 @@
+// Mixing with an implicit treap. Each node is keyed by its position in
+// the sequence (the size of everything to its left) rather than by value,
+// so duplicate values are no problem. Nodes are stored by their index in
+// the original input, and parent links let us find a node's current
+// position by walking up to the root, giving O(log n) remove and insert.
+
+const NIL: usize = usize::MAX;
+
+pub struct Mixer {
+    values: Vec<isize>,
+    priority: Vec<u64>,
+    size: Vec<usize>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    parent: Vec<usize>,
+    root: usize
+}
+
+/// Same as `linked::build_input`: the input with every value multiplied by the key.
+pub fn build_input(data: &[isize], key: isize) -> Mixer {
+    Mixer::new(data.iter().map(|n| n * key).collect())
+}
+
+/// Same as `linked::reorder`: the sequence starting from the first element
+/// of the input along with the index of the zero.
+pub fn reorder(m: &Mixer) -> (Vec<isize>, Option<usize>) {
+    let order = m.order();
+    let start = order.iter().position(|i| *i == 0).unwrap_or(0);
+    let v:Vec<isize> = order[start..].iter()
+        .chain(&order[..start])
+        .map(|i| m.values[*i])
+        .collect();
+    let zero_index = v.iter().rposition(|n| *n == 0);
+    (v, zero_index)
+}
+
+impl Mixer {
+    pub fn new(values: Vec<isize>) -> Mixer {
+        let n = values.len();
+        // xorshift keeps the priorities (and so the tree shape) repeatable
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let priority = (0..n).map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        }).collect();
+
+        let mut m = Mixer {
+            values,
+            priority,
+            size: vec![1; n],
+            left: vec![NIL; n],
+            right: vec![NIL; n],
+            parent: vec![NIL; n],
+            root: NIL
+        };
+        for i in 0..n {
+            m.root = m.merge(m.root, i);
+        }
+        m.set_root();
+        m
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn size_of(&self, t: usize) -> usize {
+        if t == NIL { 0 } else { self.size[t] }
+    }
+
+    fn update(&mut self, t: usize) {
+        let (l, r) = (self.left[t], self.right[t]);
+        self.size[t] = 1 + self.size_of(l) + self.size_of(r);
+        if l != NIL {
+            self.parent[l] = t;
+        }
+        if r != NIL {
+            self.parent[r] = t;
+        }
+    }
+
+    fn set_root(&mut self) {
+        if self.root != NIL {
+            self.parent[self.root] = NIL;
+        }
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b
+        }
+        if b == NIL {
+            return a
+        }
+        if self.priority[a] > self.priority[b] {
+            self.right[a] = self.merge(self.right[a], b);
+            self.update(a);
+            a
+        } else {
+            self.left[b] = self.merge(a, self.left[b]);
+            self.update(b);
+            b
+        }
+    }
+
+    // splits into the first k elements and the rest
+    fn split(&mut self, t: usize, k: usize) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL)
+        }
+        let left_size = self.size_of(self.left[t]);
+        if k <= left_size {
+            let (a, b) = self.split(self.left[t], k);
+            self.left[t] = b;
+            self.update(t);
+            if a != NIL {
+                self.parent[a] = NIL;
+            }
+            (a, t)
+        } else {
+            let (a, b) = self.split(self.right[t], k - left_size - 1);
+            self.right[t] = a;
+            self.update(t);
+            if b != NIL {
+                self.parent[b] = NIL;
+            }
+            (t, b)
+        }
+    }
+
+    /// Current position of the element that started at index `i`.
+    pub fn position(&self, i: usize) -> usize {
+        let mut pos = self.size_of(self.left[i]);
+        let mut x = i;
+        while self.parent[x] != NIL {
+            let p = self.parent[x];
+            if self.right[p] == x {
+                pos += self.size_of(self.left[p]) + 1;
+            }
+            x = p;
+        }
+        pos
+    }
+
+    /// Moves the element that started at index `i` forward or back by its value.
+    pub fn shift(&mut self, i: usize) {
+        let n = self.len();
+        if n < 2 {
+            return
+        }
+        let pos = self.position(i);
+
+        let (a, b) = self.split(self.root, pos);
+        let (_, c) = self.split(b, 1);
+        let rest = self.merge(a, c);
+
+        // with the element removed there are n - 1 gaps to land in
+        let to = (pos as isize + self.values[i]).rem_euclid(n as isize - 1) as usize;
+        let (a, b) = self.split(rest, to);
+        self.left[i] = NIL;
+        self.right[i] = NIL;
+        self.size[i] = 1;
+        let a = self.merge(a, i);
+        self.root = self.merge(a, b);
+        self.set_root();
+    }
+
+    /// One round of mixing: every element moves once in input order.
+    pub fn mix(&mut self) {
+        (0..self.len()).for_each(|i| self.shift(i));
+    }
+
+    /// Original indices in their current order.
+    pub fn order(&self) -> Vec<usize> {
+        let mut out = Vec::with_capacity(self.len());
+        let mut stack = vec![];
+        let mut t = self.root;
+        while t != NIL || !stack.is_empty() {
+            while t != NIL {
+                stack.push(t);
+                t = self.left[t];
+            }
+            if let Some(top) = stack.pop() {
+                out.push(top);
+                t = self.right[top];
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linked;
+
+    static EXAMPLE: [isize; 7] = [1, 2, -3, 3, -2, 0, 4];
+
+    fn mixed_linked(data: &[isize], key: isize, rounds: usize) -> (Vec<isize>, Option<usize>) {
+        let mut l = linked::build_input(data, key);
+        for _ in 0..rounds {
+            (0..l.len()).for_each(|i| linked::shift(&mut l, i));
+        }
+        linked::reorder(&l)
+    }
+
+    fn mixed(data: &[isize], key: isize, rounds: usize) -> (Vec<isize>, Option<usize>) {
+        let mut m = build_input(data, key);
+        for _ in 0..rounds {
+            m.mix();
+        }
+        reorder(&m)
+    }
+
+    #[test]
+    fn test_example() {
+        let (v, z) = mixed(&EXAMPLE, 1, 1);
+        let z = z.unwrap();
+        let coords:Vec<isize> = [1000, 2000, 3000].iter().map(|i| v[(i + z) % v.len()]).collect();
+        assert_eq!(coords, vec![4, -3, 2]);
+    }
+
+    #[test]
+    fn test_matches_linked() {
+        assert_eq!(mixed(&EXAMPLE, 1, 1), mixed_linked(&EXAMPLE, 1, 1));
+        assert_eq!(mixed(&EXAMPLE, 811589153, 10), mixed_linked(&EXAMPLE, 811589153, 10));
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let data = [3, 1, 0, 3, -1, 1, 3, -7, 0];
+        assert_eq!(mixed(&data, 1, 1), mixed_linked(&data, 1, 1));
+        assert_eq!(mixed(&data, 7, 3), mixed_linked(&data, 7, 3));
+    }
+
+    #[test]
+    fn test_matches_linked_awkward() {
+        // repeats, zeros, moves of exactly len - 1 and moves many times round
+        let data = [3, -3, 0, 3, 7, -7, 14, 0, -1, 1, 6, -6, 100, -100];
+        for (key, rounds) in [(1, 1), (1, 3), (811589153, 2)] {
+            assert_eq!(mixed(&data, key, rounds), mixed_linked(&data, key, rounds));
+        }
+    }
+}